@@ -112,6 +112,7 @@ impl Texty {
                 .spacing(5),
                 textbox(&self.spans, &self.value)
                     .on_action(Message::Action)
+                    .context_menu(true)
                     .style(|theme: &iced::Theme, status| textbox::Style {
                         background: theme.extended_palette().background.weak.color.into(),
                         border: iced::Border {
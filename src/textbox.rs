@@ -1,20 +1,27 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::Infallible;
 use std::ops::DerefMut;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
+use crate::core::keyboard::key;
 use crate::core::text::{self, Alignment, Difference, Editor, Paragraph, Text, highlighter};
 use crate::core::widget::operation::Focusable;
 use crate::core::widget::tree::{self, Tree};
 use crate::core::widget::{Id, Operation, operation};
 use crate::core::{
-    Background, Border, Color, Element, Event, Length, Padding, Pixels, Point, Radians, Rectangle,
-    Size, Vector, alignment, window,
+    Background, Border, Color, Element, Event, InputMethod, Length, Padding, Pixels, Point,
+    Radians, Rectangle, Shadow, Size, Vector, alignment, border, input_method, window,
+};
+use crate::core::{
+    Clipboard, Layout, Shell, Widget, clipboard, keyboard, layout, mouse, overlay, renderer,
 };
-use crate::core::{Clipboard, Layout, Shell, Widget, clipboard, layout, mouse, renderer};
 use crate::widget::text::{LineHeight, Shaping, Wrapping};
 use iced_graphics::geometry;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub const EDITOR_INSET: [f32; 2] = [5.0, 5.0];
 pub const INSET_VECTOR: Vector = Vector {
@@ -22,13 +29,44 @@ pub const INSET_VECTOR: Vector = Vector {
     y: EDITOR_INSET[1],
 };
 
+/// The time between scroll steps while drag selecting past the edges of a [`TextBox`].
+const AUTO_SCROLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The width of the scrollbar of a scrollable [`TextBox`].
+const SCROLLBAR_WIDTH: f32 = 6.0;
+
+/// The space between the scrollbar and the edges of a scrollable [`TextBox`].
+const SCROLLBAR_MARGIN: f32 = 2.0;
+
+/// The minimum height of the scroller of a scrollable [`TextBox`].
+const SCROLLER_MIN_HEIGHT: f32 = 12.0;
+
+mod change;
+mod chord;
+mod constraint;
+pub mod context_menu;
+mod debounce;
+mod drag;
+mod editing;
+pub mod keymap;
+mod kill_ring;
+mod recorder;
+mod secure;
+mod selection;
+mod stats;
 pub mod update;
+mod validation;
 
+pub use change::Change;
+use constraint::Constraints;
+pub use constraint::InputMask;
+use editing::Command;
 pub use iced::advanced::text::Span;
 pub use iced::widget::span;
+pub use stats::{Counts, Stats};
 pub use text::editor::{Action, Cursor, Direction, Edit, Line, LineEnding, Motion};
-use update::Update;
 pub use update::{Binding, KeyPress};
+use update::{Ime, Update};
 
 /// Creates a new [`TextBox`] with the given text.
 ///
@@ -82,7 +120,7 @@ where
 {
     id: Option<Id>,
     spans: Box<dyn AsRef<[Span<'a, Link, Renderer::Font>]> + 'a>,
-    content: &'a Content<Renderer>,
+    content: Handle<'a, Renderer>,
     rotation: Radians,
     text_size: Option<Pixels>,
     padding: Padding,
@@ -94,15 +132,46 @@ where
     align_x: text::Alignment,
     align_y: alignment::Vertical,
     wrapping: Wrapping,
+    scrollable: bool,
+    primary_selection: bool,
     class: Theme::Class<'a>,
-    key_binding: Option<Box<dyn Fn(KeyPress) -> Option<Binding<Message>> + 'a>>,
+    key_binding: Option<Box<KeyBinding<'a, Message>>>,
+    keymap: Option<&'a dyn keymap::Stateful<Message>>,
+    chords: Vec<(keymap::Chord, Binding<Message>)>,
+    caret: CaretStyle,
+    icon: Option<Icon<Renderer::Font>>,
+    on_icon_press: Option<Message>,
+    secure: bool,
+    revealable: bool,
+    max_chars: Option<usize>,
+    max_lines: Option<usize>,
+    filter: Option<Box<dyn Fn(char) -> bool + 'a>>,
+    input_mask: Option<InputMask>,
+    single_line: bool,
     on_edit: Option<Box<dyn Fn(Action) -> Message + 'a>>,
+    on_change: Option<Box<dyn Fn(Change) -> Message + 'a>>,
+    counter: bool,
+    debounced: Option<debounce::Delayed<'a, Message>>,
+    throttled: Option<debounce::Delayed<'a, Message>>,
     highlighter_settings: H::Settings,
     highlighter_format: fn(&H::Highlight, &Theme) -> highlighter::Format<Renderer::Font>,
     on_submit: Option<Message>,
     on_blur: Option<Message>,
+    on_record: Option<Box<OnRecord<'a, Message>>>,
+    on_drop: Option<Box<dyn Fn(PathBuf) -> Message + 'a>>,
+    context_menu: Option<Vec<context_menu::Entry<Message>>>,
 }
 
+/// A function mapping a [`KeyPress`] to the [`Binding`] it triggers, if any.
+type KeyBinding<'a, Message> = dyn Fn(KeyPress) -> Option<Binding<Message>> + 'a;
+
+/// A function validating the text of a [`Content`], returning the error
+/// message of a failed validation.
+type Validator = dyn Fn(&str) -> Result<(), String>;
+
+/// A function producing a message with the bindings of a recorded macro.
+type OnRecord<'a, Message> = dyn Fn(Vec<Binding<Message>>) -> Message + 'a;
+
 impl<'a, Link, Message, Theme, Renderer>
     TextBox<'a, Link, highlighter::PlainText, Message, Theme, Renderer>
 where
@@ -113,9 +182,31 @@ where
     Renderer::Font: 'a,
 {
     /// Creates a new empty [`TextBox`].
+    ///
+    /// The application owns the [`Content`], so it must perform the actions
+    /// produced by [`TextBox::on_action`] on it.
     pub fn new(
         spans: impl AsRef<[Span<'a, Link, Renderer::Font>]> + 'a,
         content: &'a Content<Renderer>,
+    ) -> Self {
+        Self::with_handle(spans, Handle::Borrowed(content))
+    }
+
+    /// Creates a new empty [`TextBox`] sharing the ownership of its
+    /// [`Content`], so it performs its own actions on it.
+    ///
+    /// The messages produced by [`TextBox::on_action`] are then only
+    /// notifications, and their actions must not be performed again.
+    pub fn shared(
+        spans: impl AsRef<[Span<'a, Link, Renderer::Font>]> + 'a,
+        content: Rc<Content<Renderer>>,
+    ) -> Self {
+        Self::with_handle(spans, Handle::Shared(content))
+    }
+
+    fn with_handle(
+        spans: impl AsRef<[Span<'a, Link, Renderer::Font>]> + 'a,
+        content: Handle<'a, Renderer>,
     ) -> Self {
         Self {
             id: None,
@@ -132,13 +223,34 @@ where
             align_x: text::Alignment::Left,
             align_y: alignment::Vertical::Top,
             wrapping: Wrapping::WordOrGlyph,
+            scrollable: false,
+            primary_selection: true,
             class: Theme::default(),
             key_binding: None,
+            keymap: None,
+            chords: Vec::new(),
+            caret: CaretStyle::default(),
+            icon: None,
+            on_icon_press: None,
+            secure: false,
+            revealable: false,
+            max_chars: None,
+            max_lines: None,
+            filter: None,
+            input_mask: None,
+            single_line: false,
             on_edit: None,
+            on_change: None,
+            counter: false,
+            debounced: None,
+            throttled: None,
             highlighter_settings: (),
             highlighter_format: |_highlight, _theme| highlighter::Format::default(),
             on_submit: None,
             on_blur: None,
+            on_record: None,
+            on_drop: None,
+            context_menu: None,
         }
     }
 
@@ -225,13 +337,77 @@ where
         self
     }
 
+    /// Sets whether the [`TextBox`] keeps its declared size and scrolls its
+    /// text when it does not fit, instead of growing to fit it.
+    pub fn scrollable(mut self, scrollable: bool) -> Self {
+        self.scrollable = scrollable;
+        self
+    }
+
+    /// Sets whether the [`TextBox`] fills the primary selection with its
+    /// selected text and pastes from it on middle click.
+    ///
+    /// This is enabled by default and only has an effect on platforms with a
+    /// primary selection, like X11 and Wayland.
+    pub fn primary_selection(mut self, primary_selection: bool) -> Self {
+        self.primary_selection = primary_selection;
+        self
+    }
+
     /// Sets the message that should be produced when some action is performed in
     /// the [`TextBox`].
+    ///
+    /// Without it, the [`TextBox`] can only be edited if it performs its own
+    /// actions; see [`TextBox::shared`].
     pub fn on_action(mut self, on_edit: impl Fn(Action) -> Message + 'a) -> Self {
         self.on_edit = Some(Box::new(on_edit));
         self
     }
 
+    /// Sets the closure to produce a message with the [`Change`] of every
+    /// edit, once it is performed on the [`Content`].
+    pub fn on_change(mut self, on_change: impl Fn(Change) -> Message + 'a) -> Self {
+        self.content.track_changes();
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    /// Sets the closure to produce a message with the text of the
+    /// [`TextBox`] once it stops changing for the given delay.
+    ///
+    /// A pending change is published right away when the [`TextBox`] is
+    /// blurred or submitted.
+    pub fn on_change_debounced(
+        mut self,
+        delay: Duration,
+        on_change: impl Fn(String) -> Message + 'a,
+    ) -> Self {
+        self.debounced = Some(debounce::Delayed {
+            mode: debounce::Mode::Debounce,
+            delay,
+            on_change: Box::new(on_change),
+        });
+        self
+    }
+
+    /// Sets the closure to produce a message with the text of the
+    /// [`TextBox`] at most once every given interval while it changes.
+    ///
+    /// A pending change is published right away when the [`TextBox`] is
+    /// blurred or submitted.
+    pub fn on_change_throttled(
+        mut self,
+        interval: Duration,
+        on_change: impl Fn(String) -> Message + 'a,
+    ) -> Self {
+        self.throttled = Some(debounce::Delayed {
+            mode: debounce::Mode::Throttle,
+            delay: interval,
+            on_change: Box::new(on_change),
+        });
+        self
+    }
+
     /// Sets the message that should be produced when this [`TextBox`] is submitted.
     pub fn on_submit(mut self, on_submit: Message) -> Self {
         self.on_submit = Some(on_submit);
@@ -244,6 +420,26 @@ where
         self
     }
 
+    /// Sets the message that should be produced when a file is dropped onto
+    /// this [`TextBox`].
+    ///
+    /// The caret is moved to the drop position beforehand, so the contents of the
+    /// file can be inserted right where they were dropped.
+    pub fn on_drop(mut self, on_drop: impl Fn(PathBuf) -> Message + 'a) -> Self {
+        self.on_drop = Some(Box::new(on_drop));
+        self
+    }
+
+    /// Sets the closure to produce a message with the macro recorded between
+    /// [`Binding::StartRecording`] and [`Binding::StopRecording`].
+    ///
+    /// The macro only holds built-in bindings, so it can be saved with the
+    /// `serde` feature and played back later as a [`Binding::Sequence`].
+    pub fn on_record(mut self, on_record: impl Fn(Vec<Binding<Message>>) -> Message + 'a) -> Self {
+        self.on_record = Some(Box::new(on_record));
+        self
+    }
+
     /// Sets the closure to produce key bindings on key presses.
     ///
     /// See [`Binding`] for the list of available bindings.
@@ -255,894 +451,3295 @@ where
         self
     }
 
-    #[must_use]
-    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
-    where
-        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
-    {
-        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+    /// Sets the [`keymap::Stateful`] keymap producing the key bindings of the
+    /// [`TextBox`], like [`keymap::Vim`].
+    ///
+    /// This replaces any closure set with [`TextBox::key_binding`].
+    pub fn keymap(mut self, keymap: &'a impl keymap::Stateful<Message>) -> Self {
+        self.keymap = Some(keymap);
+        self.key_binding = Some(Box::new(move |key_press| keymap.key_press(key_press)));
         self
     }
 
-    /// Sets the style class of the [`TextBox`].
-    #[must_use]
-    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
-        self.class = class.into();
+    /// Binds a [`keymap::Chord`] of one or more key presses, like
+    /// `ctrl+k ctrl+c`, to the given [`Binding`].
+    ///
+    /// While a chord is pending, the [`TextBox`] shows the keys pressed so
+    /// far and waits for the next one. If it does not complete any chord in
+    /// time, the buffered keys are handled as normal input.
+    pub fn chord(mut self, chord: impl Into<keymap::Chord>, binding: Binding<Message>) -> Self {
+        self.chords.push((chord.into(), binding));
         self
     }
-}
-
-pub struct Content<R = iced::Renderer>(RefCell<Internal<R>>)
-where
-    R: text::Renderer;
 
-struct Internal<R>
-where
-    R: text::Renderer,
-{
-    editor: R::Editor,
-    is_dirty: bool,
-}
+    /// Binds all the given [`keymap::Chord`]s, like the ones of
+    /// [`keymap::Keymap::chords`].
+    pub fn chords(
+        mut self,
+        chords: impl IntoIterator<Item = (keymap::Chord, Binding<Message>)>,
+    ) -> Self {
+        self.chords.extend(chords);
+        self
+    }
 
-impl<R> Content<R>
-where
-    R: text::Renderer,
-{
-    /// Creates an empty [`Content`].
-    pub fn new() -> Self {
-        Self::with_text("")
+    /// Sets the [`CaretStyle`] of the [`TextBox`], like its shape or whether
+    /// it blinks.
+    ///
+    /// The color of the caret is set by [`Style::caret`].
+    pub fn caret(mut self, caret: CaretStyle) -> Self {
+        self.caret = caret;
+        self
     }
 
-    /// Creates a [`Content`] with the given text.
-    pub fn with_text(text: &str) -> Self {
-        Self(RefCell::new(Internal {
-            editor: R::Editor::with_text(text),
-            is_dirty: true,
-        }))
+    /// Sets the [`Icon`] of the [`TextBox`], shown next to the text.
+    pub fn icon(mut self, icon: Icon<Renderer::Font>) -> Self {
+        self.icon = Some(icon);
+        self
     }
 
-    /// Performs an [`Action`] on the [`Content`].
-    pub fn perform(&mut self, action: Action) {
-        let internal = self.0.get_mut();
+    /// Sets the message to produce when the [`Icon`] of the [`TextBox`] is
+    /// pressed, like clearing its text.
+    pub fn on_icon_press(mut self, on_icon_press: Message) -> Self {
+        self.on_icon_press = Some(on_icon_press);
+        self
+    }
 
-        internal.editor.perform(action);
-        internal.is_dirty = true;
+    /// Sets whether the [`TextBox`] is secure, like a password field.
+    ///
+    /// A secure [`TextBox`] masks its text while editing and displaying it,
+    /// and never copies it to the clipboard.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
     }
 
-    /// Returns the amount of lines of the [`Content`].
-    pub fn line_count(&self) -> usize {
-        self.0.borrow().editor.line_count()
+    /// Sets whether pressing the [`Icon`] of a secure [`TextBox`] toggles
+    /// revealing its text.
+    pub fn revealable(mut self, revealable: bool) -> Self {
+        self.revealable = revealable;
+        self
     }
 
-    /// Returns the text of the line at the given index, if it exists.
-    pub fn line(&self, index: usize) -> Option<Line<'_>> {
-        let internal = self.0.borrow();
-        let line = internal.editor.line(index)?;
+    /// Sets the maximum amount of characters of the [`TextBox`], not counting
+    /// line breaks.
+    ///
+    /// Inserted text is truncated to fit.
+    pub fn max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
 
-        Some(Line {
-            text: Cow::Owned(line.text.into_owned()),
-            ending: line.ending,
-        })
+    /// Sets the maximum amount of lines of the [`TextBox`].
+    ///
+    /// Inserted text is truncated before the line break that would exceed it.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
     }
 
-    /// Returns an iterator of the text of the lines in the [`Content`].
-    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
-        (0..)
-            .map(|i| self.line(i))
-            .take_while(Option::is_some)
-            .flatten()
+    /// Sets the closure deciding which characters can be inserted in the
+    /// [`TextBox`]; any other character is dropped.
+    pub fn filter(mut self, filter: impl Fn(char) -> bool + 'a) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
     }
 
-    /// Returns the text of the [`Content`].
-    pub fn text(&self) -> String {
-        let mut contents = String::new();
-        let mut lines = self.lines().peekable();
+    /// Sets the [`InputMask`] of the [`TextBox`], like `"(999) 999-9999"`.
+    ///
+    /// The text is kept to a single line formatted by the mask, whose
+    /// remaining characters are shown after the text while editing.
+    pub fn input_mask(mut self, input_mask: impl Into<InputMask>) -> Self {
+        self.input_mask = Some(input_mask.into());
+        self
+    }
 
-        while let Some(line) = lines.next() {
-            contents.push_str(&line.text);
+    /// Sets whether the [`TextBox`] holds a single line of text.
+    ///
+    /// In a single-line [`TextBox`], Enter submits, inserted line breaks are
+    /// replaced with spaces, and the text scrolls horizontally to follow the
+    /// caret instead of wrapping.
+    pub fn single_line(mut self, single_line: bool) -> Self {
+        self.single_line = single_line;
+        self
+    }
 
-            if lines.peek().is_some() {
-                contents.push_str(if line.ending == LineEnding::None {
-                    LineEnding::default().as_str()
-                } else {
-                    line.ending.as_str()
-                });
-            }
-        }
+    /// Sets the function that validates the text of the [`TextBox`] after
+    /// each edit.
+    ///
+    /// While the text is invalid, the [`TextBox`] is drawn with the
+    /// [`Status::Invalid`] style and cannot be submitted. A non-empty error
+    /// message is shown under the [`TextBox`] while it is focused.
+    ///
+    /// The function is kept in the [`Content`], which validates its text
+    /// as soon as it is edited, along with the result of the last validation.
+    pub fn validate(self, validate: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        self.content.set_validator(Rc::new(validate));
+        self
+    }
 
-        contents
+    /// Sets whether the [`TextBox`] shows the amount of characters of its
+    /// text at its bottom right corner, out of its [`TextBox::max_chars`].
+    pub fn counter(mut self, counter: bool) -> Self {
+        self.counter = counter;
+        self
     }
 
-    /// Returns the kind of [`LineEnding`] used for separating lines in the [`Content`].
-    pub fn line_ending(&self) -> Option<LineEnding> {
-        Some(self.line(0)?.ending)
+    /// Sets whether the [`TextBox`] shows a context menu on right click.
+    ///
+    /// The context menu starts with the default [`context_menu::Entry`] list.
+    pub fn context_menu(mut self, enabled: bool) -> Self {
+        self.context_menu = enabled.then(context_menu::Entry::defaults);
+        self
     }
 
-    /// Returns the selected text of the [`Content`].
-    pub fn selection(&self) -> Option<String> {
-        self.0.borrow().editor.selection()
+    /// Adds an entry to the context menu of the [`TextBox`], enabling it if needed.
+    pub fn context_menu_entry(mut self, entry: context_menu::Entry<Message>) -> Self {
+        self.context_menu
+            .get_or_insert_with(context_menu::Entry::defaults)
+            .push(entry);
+        self
     }
 
-    /// Returns the current cursor position of the [`Content`].
-    pub fn cursor_position(&self) -> (usize, usize) {
-        self.0.borrow().editor.cursor_position()
+    #[must_use]
+    pub fn style(mut self, style: impl Fn(&Theme, Status) -> Style + 'a) -> Self
+    where
+        Theme::Class<'a>: From<StyleFn<'a, Theme>>,
+    {
+        self.class = (Box::new(style) as StyleFn<'a, Theme>).into();
+        self
     }
-}
 
-impl<Renderer> Default for Content<Renderer>
-where
-    Renderer: text::Renderer,
-{
-    fn default() -> Self {
-        Self::new()
+    /// Sets the style class of the [`TextBox`].
+    #[must_use]
+    pub fn class(mut self, class: impl Into<Theme::Class<'a>>) -> Self {
+        self.class = class.into();
+        self
     }
 }
 
-impl<Renderer> std::fmt::Debug for Content<Renderer>
+impl<'a, Link, H, Message, Theme, Renderer> TextBox<'a, Link, H, Message, Theme, Renderer>
 where
-    Renderer: text::Renderer,
-    Renderer::Editor: std::fmt::Debug,
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    Message: std::fmt::Debug + Clone + 'a,
+    Theme: Catalog,
+    Renderer: text::Renderer + 'a,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let internal = self.0.borrow();
+    /// Returns the [`InputMethod`] strategy of the [`TextBox`] for the current
+    /// [`State`], reporting the caret as the area the candidate window must
+    /// not cover.
+    fn input_method(
+        &self,
+        state: &State<Link, H, Renderer::Paragraph>,
+        renderer: &Renderer,
+        layout: Layout<'_>,
+    ) -> InputMethod {
+        let Some(Focus {
+            is_window_focused: true,
+            ..
+        }) = &state.focus
+        else {
+            return InputMethod::Disabled;
+        };
 
-        f.debug_struct("Content")
-            .field("editor", &internal.editor)
-            .field("is_dirty", &internal.is_dirty)
-            .finish()
-    }
-}
+        let internal = self.content.0.borrow();
 
-struct State<Link, H: highlighter::Highlighter, P: Paragraph> {
-    focus: Option<Focus>,
-    last_click: Option<mouse::Click>,
-    drag_click: Option<mouse::click::Kind>,
-    partial_scroll: f32,
-    highlighter: RefCell<H>,
-    highlighter_settings: H::Settings,
-    highlighter_format_address: usize,
-    paragraph: P,
-    spans: Vec<Span<'static, Link, P::Font>>,
-}
+        let caret = caret_position(if self.is_masked(state) {
+            self.masked_cursor(renderer)
+        } else {
+            internal.editor.cursor()
+        });
+
+        let preedit_offset = state
+            .preedit
+            .as_ref()
+            .map(|preedit| {
+                let index = preedit
+                    .selection
+                    .as_ref()
+                    .map_or(preedit.content.len(), |selection| selection.start);
+
+                preedit_x(&state.preedit_paragraph, &preedit.content, index)
+            })
+            .unwrap_or_default();
+
+        let line_height = self
+            .line_height
+            .to_absolute(self.text_size.unwrap_or_else(|| renderer.default_size()));
+
+        let editor_position = layout
+            .children()
+            .nth(2)
+            .map_or(layout.position(), |editor_layout| editor_layout.position());
+
+        let position = caret + (editor_position - Point::ORIGIN) + INSET_VECTOR
+            - Vector::new(state.scroll_offset_x, state.scroll_offset)
+            + Vector::new(preedit_offset, 0.0);
+
+        InputMethod::Enabled {
+            cursor: Rectangle::new(position, Size::new(1.0, f32::from(line_height))),
+            purpose: if self.secure {
+                input_method::Purpose::Secure
+            } else {
+                input_method::Purpose::Normal
+            },
+            // We draw the preedit ourselves, on the spot
+            preedit: None,
+        }
+    }
 
-#[derive(Debug, Clone, Copy)]
-struct Focus {
-    updated_at: Instant,
-    now: Instant,
-    is_window_focused: bool,
-}
+    /// Returns the [`Binding`] produced by the given [`KeyPress`] on its own.
+    fn resolve_key_press(&self, key_press: KeyPress) -> Option<Binding<Message>> {
+        match &self.key_binding {
+            Some(key_binding) => key_binding(key_press),
+            None => Binding::from_key_press(key_press),
+        }
+    }
 
-impl Focus {
-    const CURSOR_BLINK_INTERVAL_MILLIS: u128 = 500;
+    /// Handles the [`chord::Resolution`] of a key press, returning true if
+    /// some chord consumed it.
+    fn handle_chord(
+        &self,
+        resolution: chord::Resolution<Message>,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        target: &Target<'_, Renderer, Message>,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> bool {
+        match resolution {
+            chord::Resolution::Unmatched => false,
+            chord::Resolution::Pending => {
+                if let Some(pending) = &state.chord {
+                    shell.request_redraw_at(window::RedrawRequest::At(
+                        pending.started_at + chord::TIMEOUT,
+                    ));
+                }
 
-    fn now() -> Self {
-        let now = Instant::now();
+                shell.capture_event();
+                shell.request_redraw();
+                true
+            }
+            chord::Resolution::Matched(binding) => {
+                handle_binding(binding, target, state, clipboard, shell);
+                shell.capture_event();
+                true
+            }
+            chord::Resolution::Replay(key_presses, last) => {
+                self.replay(key_presses, state, target, clipboard, shell);
+                shell.capture_event();
 
-        Self {
-            updated_at: now,
-            now,
-            is_window_focused: true,
+                // The key press that broke the chord is handled as normal
+                // input, unless it starts some other chord
+                self.handle_chord(*last, state, target, clipboard, shell)
+            }
         }
     }
 
-    fn is_cursor_visible(&self) -> bool {
-        self.is_window_focused
-            && ((self.now - self.updated_at).as_millis() / Self::CURSOR_BLINK_INTERVAL_MILLIS) % 2
-                == 0
+    /// Handles the key presses of a chord that was broken or timed out as
+    /// normal input.
+    fn replay(
+        &self,
+        key_presses: Vec<KeyPress>,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        target: &Target<'_, Renderer, Message>,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        for key_press in key_presses {
+            if let Some(binding) = self.resolve_key_press(key_press) {
+                handle_binding(binding, target, state, clipboard, shell);
+            }
+        }
     }
 }
 
-impl<Link, H: highlighter::Highlighter, P: Paragraph> operation::Focusable for State<Link, H, P> {
-    fn is_focused(&self) -> bool {
-        self.focus.is_some()
-    }
-
-    fn focus(&mut self) {
-        self.focus = Some(Focus::now());
-    }
-
-    fn unfocus(&mut self) {
-        self.focus = None;
-    }
-}
-
-// Layout function for the editor, following text_editor's pattern
-fn layout_editor<H, Renderer>(
-    editor: &mut Renderer::Editor,
-    limits: &layout::Limits,
-    padding: Padding,
-    font: Renderer::Font,
-    text_size: Pixels,
-    line_height: LineHeight,
-    wrapping: Wrapping,
-    highlighter: &mut H,
-) -> layout::Node
+impl<'a, Link, H, Message, Theme, Renderer> TextBox<'a, Link, H, Message, Theme, Renderer>
 where
+    Link: Clone + 'static,
     H: highlighter::Highlighter,
-    Renderer: text::Renderer,
+    Message: std::fmt::Debug + Clone + 'a,
+    Theme: Catalog,
+    Renderer: text::Renderer + 'a,
 {
-    // First make the editor infinitely tall so that we can measure how much the
-    // text would take up with `editor.min_bounds()` We need this because the
-    // LayoutRuns used by `text::measure` to calculate the size of the content
-    // will be limited to the lines that fit the editor's bounds.
-    editor.update(
-        Size::new(editor.bounds().width, f32::INFINITY),
-        font,
-        text_size,
-        line_height,
-        wrapping,
-        highlighter,
-    );
+    /// Returns the [`Constraints`] on the text inserted in the [`TextBox`].
+    fn constraints(&self) -> Constraints<'_> {
+        Constraints {
+            max_chars: self.max_chars,
+            max_lines: self.max_lines,
+            filter: self.filter.as_deref(),
+            mask: self.input_mask.as_ref(),
+            is_single_line: self.single_line,
+        }
+    }
 
-    // Get the bounds needed to fit this whole text
-    let min_bounds = editor.min_bounds();
-
-    // Resize to either the layout limits or whatever we need for the editor
-    let node = layout::Node::new(Size::new(
-        limits.max().width + padding.horizontal(),
-        limits
-            .max()
-            .height
-            .max(min_bounds.height + padding.vertical()),
-    ));
-
-    // Update editor with the final computed size
-    editor.update(
-        node.size(),
-        font,
-        text_size,
-        line_height,
-        wrapping,
-        highlighter,
-    );
+    /// Returns true if the [`TextBox`] performs its own actions on its
+    /// [`Content`].
+    fn performs_actions(&self) -> bool {
+        matches!(self.content, Handle::Shared(_))
+    }
 
-    node
-}
+    /// Returns the [`Publisher`] of the [`Action`]s of the [`TextBox`].
+    fn publisher(&self) -> Publisher<'_, Renderer, Message> {
+        Publisher {
+            content: &self.content,
+            on_edit: self.on_edit.as_deref(),
+            on_change: self.on_change.as_deref(),
+            perform: self.performs_actions(),
+        }
+    }
 
-// Layout function for background, taking maximum space after padding
-fn layout_background(limits: &layout::Limits, width: Length, height: Length) -> layout::Node {
-    layout::Node::new(limits.resolve(width, height, Size::ZERO))
-}
+    /// Returns the [`Target`] of the [`Binding`]s of the [`TextBox`].
+    fn target(&self) -> Target<'_, Renderer, Message> {
+        Target {
+            content: &self.content,
+            constraints: self.constraints(),
+            publisher: self.publisher(),
+            on_submit: &self.on_submit,
+            on_blur: &self.on_blur,
+            on_record: self.on_record.as_deref(),
+            debounced: self.debounced.as_ref(),
+            throttled: self.throttled.as_ref(),
+        }
+    }
 
-impl<'a, Link, H, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
-    for TextBox<'a, Link, H, Message, Theme, Renderer>
-where
-    Link: Clone + 'static,
-    H: highlighter::Highlighter,
-    Message: std::fmt::Debug + Clone + 'a,
-    Theme: Catalog,
-    Renderer: text::Renderer<Font = iced::Font> + geometry::Renderer + 'a,
-    Renderer::Paragraph: 'a,
-{
-    fn layout(
-        &self,
-        tree: &mut Tree,
-        renderer: &Renderer,
-        limits: &layout::Limits,
-    ) -> layout::Node {
-        let state = tree
-            .state
-            .downcast_mut::<State<Link, H, Renderer::Paragraph>>();
+    /// Returns true if the text of the [`TextBox`] is masked.
+    fn is_masked(&self, state: &State<Link, H, Renderer::Paragraph>) -> bool {
+        self.secure && !state.is_revealed
+    }
 
-        // Calculate content bounds with padding
-        let padding = self.padding.fit(Size::ZERO, limits.max());
-        let content_limits = limits.width(self.width).height(self.height).shrink(padding);
+    /// Returns true if pressing the [`Icon`] of the [`TextBox`] does something.
+    fn is_icon_pressable(&self) -> bool {
+        self.on_icon_press.is_some() || (self.secure && self.revealable)
+    }
+
+    /// Returns the [`Cursor`] of the masked text of the [`TextBox`].
+    fn masked_cursor(&self, renderer: &Renderer) -> Cursor {
+        let (mask_width, line_height) = self.mask_size(renderer);
+
+        secure::cursor(
+            &self.content_lines(),
+            self.content.cursor_position(),
+            self.content.selection_bounds(),
+            mask_width,
+            line_height,
+        )
+    }
+
+    /// Returns the width of a single mask character and the height of a line
+    /// of the masked text of the [`TextBox`].
+    fn mask_size(&self, renderer: &Renderer) -> (f32, f32) {
+        (
+            self.text_width(&secure::MASK.to_string(), renderer),
+            self.line_height_in_pixels(renderer),
+        )
+    }
 
+    /// Returns the width of the given single line of text in the [`TextBox`].
+    fn text_width(&self, text: &str, renderer: &Renderer) -> f32 {
         let text_size = self.text_size.unwrap_or_else(|| renderer.default_size());
         let font = self.font.unwrap_or_else(|| renderer.default_font());
 
-        // Layout each component
-        let mut spans_node = layout_spans(
-            state,
-            renderer,
-            &content_limits,
-            self.width,
-            self.height,
-            self.spans.as_ref().as_ref(),
-            self.line_height,
-            text_size,
-            font,
-            self.align_x,
-            self.align_y,
-            self.wrapping,
+        text_width::<Renderer::Paragraph>(text, text_size, self.line_height, font)
+    }
+
+    /// Returns the text of every line of the [`Content`] of the [`TextBox`].
+    fn content_lines(&self) -> Vec<String> {
+        self.content
+            .lines()
+            .map(|line| line.text.into_owned())
+            .collect()
+    }
+
+    /// Publishes the given click or drag [`Action`] at some position of the
+    /// [`TextBox`].
+    ///
+    /// The editor hit tests the glyphs of the actual text, so the position
+    /// in a masked text is moved to the same grapheme boundary of the actual
+    /// text. Lines of a masked text never wrap.
+    fn publish_pointer(
+        &self,
+        action: Action,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        renderer: &Renderer,
+        publisher: &Publisher<'_, Renderer, Message>,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let position = match action {
+            Action::Click(position) | Action::Drag(position) if self.is_masked(state) => position,
+            action => {
+                publisher.publish(shell, action);
+                return;
+            }
+        };
+
+        let lines = self.content_lines();
+        let (mask_width, line_height) = self.mask_size(renderer);
+
+        let (line, column) = secure::hit_test(&lines, position, mask_width, line_height);
+
+        // The middle of the line, right at the boundary of its graphemes
+        let position = Point::new(
+            lines
+                .get(line)
+                .map_or(0.0, |text| self.text_width(&text[..column], renderer)),
+            (line as f32 + 0.5) * line_height,
         );
 
-        let background_node = layout_background(&limits, self.width, self.height);
+        publisher.publish(
+            shell,
+            match action {
+                Action::Click(_) => Action::Click(position),
+                _ => Action::Drag(position),
+            },
+        );
+    }
 
-        let mut internal = self.content.0.borrow_mut();
+    /// Returns the padding around the text of the [`TextBox`], including the
+    /// space taken by its [`Icon`].
+    fn text_padding(&self, layout: Layout<'_>) -> Padding {
+        let (Some(icon), Some(icon_layout)) = (&self.icon, layout.children().nth(3)) else {
+            return self.padding;
+        };
 
-        if state.highlighter_format_address != self.highlighter_format as usize {
-            state.highlighter.borrow_mut().change_line(0);
+        let space = icon_layout.bounds().width + icon.spacing;
 
-            state.highlighter_format_address = self.highlighter_format as usize;
+        match icon.side {
+            Side::Left => self.padding.left(self.padding.left + space),
+            Side::Right => self.padding.right(self.padding.right + space),
         }
+    }
 
-        if state.highlighter_settings != self.highlighter_settings {
-            state
-                .highlighter
-                .borrow_mut()
-                .update(&self.highlighter_settings);
+    /// Publishes the text of the [`Content`] through the debounced and
+    /// throttled closures whose delay is over, scheduling a redraw for the
+    /// others.
+    fn publish_delayed(
+        &self,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let now = Instant::now();
+        let edits = self.content.edits();
+        let is_focused = state.is_focused();
+        let mut text = None;
+
+        for (delayed, timer) in [
+            (&self.debounced, &mut state.debounce),
+            (&self.throttled, &mut state.throttle),
+        ] {
+            let Some(delayed) = delayed else {
+                continue;
+            };
+
+            match timer.poll(delayed.mode, delayed.delay, edits, now, is_focused) {
+                debounce::Poll::Idle => {}
+                debounce::Poll::Pending(due) => {
+                    shell.request_redraw_at(window::RedrawRequest::At(due));
+                }
+                debounce::Poll::Due => {
+                    let text = text.get_or_insert_with(|| self.content.text());
 
-            state.highlighter_settings = self.highlighter_settings.clone();
+                    shell.publish((delayed.on_change)(text.clone()));
+                }
+            }
         }
+    }
 
-        let editor_node = layout_editor::<H, Renderer>(
-            &mut internal.editor,
-            &content_limits,
-            self.padding,
-            font,
-            text_size,
-            self.line_height,
-            self.wrapping,
-            state.highlighter.borrow_mut().deref_mut(),
-        );
+    /// Scrolls a single-line [`TextBox`] horizontally, so its caret stays
+    /// visible.
+    fn follow_caret(
+        &self,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) {
+        let Some(editor_layout) = layout.children().nth(2) else {
+            return;
+        };
 
-        // Position the spans and editor nodes within the padded space
-        spans_node = spans_node
-            .align(
-                alignment::Alignment::Start,
-                alignment::Alignment::from(self.align_y),
-                content_limits.max(),
-            )
-            .move_to(Point::new(padding.left, padding.top));
+        if !self.single_line || !state.is_focused() {
+            state.scroll_offset_x = 0.0;
+            return;
+        }
 
-        // Use the maximum of content_bounds and editor's required size,
-        // but only if we're focused
-        let final_bounds = if state.is_focused() {
-            Size::new(
-                content_limits.max().width,
-                content_limits.max().height.max(editor_node.size().height),
-            )
-            .expand(padding)
+        let width = editor_layout.bounds().shrink(EDITOR_INSET).width;
+
+        let cursor = if self.is_masked(state) {
+            self.masked_cursor(renderer)
         } else {
-            limits.resolve(
-                spans_node.size().width + padding.horizontal(),
-                spans_node.size().height + padding.vertical(),
-                Size::ZERO,
-            )
+            self.content.0.borrow().editor.cursor()
         };
 
-        layout::Node::with_children(final_bounds, vec![spans_node, background_node, editor_node])
-    }
+        let (start, end) = match cursor {
+            Cursor::Caret(position) => (position.x, position.x + self.caret.width),
+            Cursor::Selection(ranges) => ranges
+                .first()
+                .map_or((0.0, 0.0), |range| (range.x, range.x + range.width)),
+        };
 
-    fn tag(&self) -> tree::Tag {
-        tree::Tag::of::<State<Link, H, Renderer::Paragraph>>()
+        // Follow whichever end of the selection went out of sight
+        if start < state.scroll_offset_x {
+            state.scroll_offset_x = start;
+        } else if end > state.scroll_offset_x + width {
+            state.scroll_offset_x = end - width;
+        }
+
+        state.scroll_offset_x = state.scroll_offset_x.max(0.0);
     }
 
-    fn state(&self) -> tree::State {
-        tree::State::new(State::<Link, H, _> {
-            paragraph: Renderer::Paragraph::default(),
-            focus: None,
-            last_click: None,
-            drag_click: None,
-            partial_scroll: 0.0,
-            spans: Vec::new(),
-            highlighter: RefCell::new(H::new(&self.highlighter_settings)),
-            highlighter_settings: self.highlighter_settings.clone(),
-            highlighter_format_address: self.highlighter_format as usize,
-        })
+    /// Returns the line height of the [`TextBox`] text, in pixels.
+    fn line_height_in_pixels(&self, renderer: &Renderer) -> f32 {
+        f32::from(
+            self.line_height
+                .to_absolute(self.text_size.unwrap_or_else(|| renderer.default_size())),
+        )
     }
 
-    fn size(&self) -> Size<Length> {
-        Size {
-            width: self.width,
-            height: self.height,
+    /// Returns how far the text can be scrolled in a scrollable [`TextBox`].
+    fn max_scroll_offset(
+        &self,
+        state: &State<Link, H, Renderer::Paragraph>,
+        layout: Layout<'_>,
+    ) -> f32 {
+        if !self.scrollable {
+            return 0.0;
         }
+
+        let content_height = if state.is_focused() {
+            layout
+                .children()
+                .nth(2)
+                .map(|editor_layout| editor_layout.bounds().height)
+                .unwrap_or_default()
+        } else {
+            state.paragraph.min_bounds().height + self.padding.vertical()
+        };
+
+        (content_height - layout.bounds().height).max(0.0)
     }
 
-    fn draw(
+    /// Returns the current scroll offset of the text, clamped to the content.
+    fn scroll_offset(
         &self,
-        tree: &Tree,
-        renderer: &mut Renderer,
-        theme: &Theme,
-        defaults: &renderer::Style,
+        state: &State<Link, H, Renderer::Paragraph>,
         layout: Layout<'_>,
-        cursor: mouse::Cursor,
-        viewport: &Rectangle,
+    ) -> f32 {
+        state
+            .scroll_offset
+            .clamp(0.0, self.max_scroll_offset(state, layout))
+    }
+
+    /// Scrolls the text by the given amount of pixels.
+    fn scroll_by(
+        &self,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        layout: Layout<'_>,
+        delta: f32,
     ) {
-        let state = tree
-            .state
-            .downcast_ref::<State<Link, H, Renderer::Paragraph>>();
+        state.scroll_offset = (self.scroll_offset(state, layout) + delta)
+            .clamp(0.0, self.max_scroll_offset(state, layout));
+    }
+
+    /// Returns the bounds of the scrollbar, if the text overflows a scrollable
+    /// [`TextBox`].
+    fn scrollbar(
+        &self,
+        state: &State<Link, H, Renderer::Paragraph>,
+        layout: Layout<'_>,
+    ) -> Option<Scrollbar> {
+        let max_scroll_offset = self.max_scroll_offset(state, layout);
+
+        if max_scroll_offset <= 0.0 {
+            return None;
+        }
 
         let bounds = layout.bounds();
 
-        let is_mouse_over = cursor.is_over(bounds);
+        let rail = Rectangle {
+            x: bounds.x + bounds.width - SCROLLBAR_WIDTH - SCROLLBAR_MARGIN,
+            y: bounds.y + SCROLLBAR_MARGIN,
+            width: SCROLLBAR_WIDTH,
+            height: bounds.height - 2.0 * SCROLLBAR_MARGIN,
+        };
 
-        let status = if state.focus.is_some() {
-            Status::Focused
-        } else if is_mouse_over {
-            Status::Hovered
-        } else {
-            Status::Active
+        let height = (rail.height * bounds.height / (bounds.height + max_scroll_offset))
+            .max(SCROLLER_MIN_HEIGHT)
+            .min(rail.height);
+
+        let scroller = Rectangle {
+            y: rail.y
+                + (rail.height - height) * self.scroll_offset(state, layout) / max_scroll_offset,
+            height,
+            ..rail
         };
 
-        let style = theme.style(&self.class, status);
+        Some(Scrollbar { rail, scroller })
+    }
 
-        let mut children_layout = layout.children();
-        let spans_layout = children_layout.next().unwrap();
-        let background_layout = children_layout.next().unwrap();
-        let editor_layout = children_layout.next().unwrap();
+    /// Scrolls a scrollable [`TextBox`] with the mouse wheel, its scrollbar or,
+    /// when not editing, the navigation keys.
+    ///
+    /// Returns true if the event was handled.
+    fn update_scroll(
+        &self,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+    ) -> bool {
+        let bounds = layout.bounds();
+        let line_height = self.line_height_in_pixels(renderer);
+        let scrollbar = self.scrollbar(state, layout);
 
-        let translation = layout.position() - Point::ORIGIN;
+        match event {
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if cursor.is_over(bounds) => {
+                self.scroll_by(state, layout, update::scroll_lines(delta) * line_height);
 
-        let hovered_span = cursor
-            .position_in(layout.bounds())
-            .and_then(|position| state.paragraph.hit_span(position));
+                scrollbar.is_some()
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some((scrollbar, position)) = scrollbar
+                    .zip(cursor.position())
+                    .filter(|(scrollbar, position)| scrollbar.rail.contains(*position))
+                else {
+                    return false;
+                };
+
+                let grabbed_at = if scrollbar.scroller.contains(position) {
+                    position.y - scrollbar.scroller.y
+                } else {
+                    scrollbar.scroller.height / 2.0
+                };
 
-        let text_bounds = layout.bounds();
+                state.scroller_grabbed_at = Some(grabbed_at);
 
-        if !state.is_focused() {
-            // Draw a stroke around the whole object
-            renderer.fill_quad(
-                renderer::Quad {
-                    bounds: background_layout.bounds(),
-                    border: style.border,
-                    ..renderer::Quad::default()
-                },
-                style.background,
-            );
+                self.drag_scroller(state, layout, scrollbar, position.y - grabbed_at);
 
-            for (index, span) in self.spans.as_ref().as_ref().into_iter().enumerate() {
-                let is_hovered_link = span.link.is_some() && Some(index) == hovered_span;
+                true
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some((grabbed_at, scrollbar)) = state.scroller_grabbed_at.zip(scrollbar) else {
+                    return false;
+                };
 
-                if span.highlight.is_some()
-                    || span.underline
-                    || span.strikethrough
-                    || is_hovered_link
-                {
-                    let regions = state.paragraph.span_bounds(index);
-
-                    if let Some(highlight) = span.highlight {
-                        for bounds in &regions {
-                            let bounds = Rectangle::new(
-                                bounds.position()
-                                    - Vector::new(span.padding.left, span.padding.top),
-                                bounds.size()
-                                    + Size::new(span.padding.horizontal(), span.padding.vertical()),
-                            );
+                self.drag_scroller(state, layout, scrollbar, position.y - grabbed_at);
 
-                            renderer.fill_quad(
-                                renderer::Quad {
-                                    bounds: bounds + translation,
-                                    border: highlight.border,
-                                    ..Default::default()
-                                },
-                                highlight.background,
-                            );
-                        }
-                    }
+                true
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.scroller_grabbed_at.take().is_some()
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key),
+                ..
+            }) if !state.is_focused() && cursor.is_over(bounds) && scrollbar.is_some() => {
+                let page = bounds.height - self.padding.vertical();
+
+                let delta = match key {
+                    key::Named::ArrowUp => -line_height,
+                    key::Named::ArrowDown => line_height,
+                    key::Named::PageUp => -page,
+                    key::Named::PageDown => page,
+                    key::Named::Home => f32::NEG_INFINITY,
+                    key::Named::End => f32::INFINITY,
+                    _ => return false,
+                };
+
+                self.scroll_by(state, layout, delta);
+
+                true
+            }
+            _ => false,
+        }
+    }
 
-                    if span.underline || span.strikethrough || is_hovered_link {
-                        let size = span
-                            .size
-                            .or(self.text_size)
-                            .unwrap_or(renderer.default_size());
+    /// Scrolls the text so the scroller starts at the given vertical position.
+    fn drag_scroller(
+        &self,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        layout: Layout<'_>,
+        scrollbar: Scrollbar,
+        scroller_y: f32,
+    ) {
+        let max_scroll_offset = self.max_scroll_offset(state, layout);
+        let range = scrollbar.rail.height - scrollbar.scroller.height;
 
-                        let line_height = span
-                            .line_height
-                            .unwrap_or(self.line_height)
-                            .to_absolute(size);
+        if range > 0.0 {
+            state.scroll_offset = ((scroller_y - scrollbar.rail.y) / range * max_scroll_offset)
+                .clamp(0.0, max_scroll_offset);
+        }
+    }
 
-                        // let color = span.color.or(style.value).unwrap_or(defaults.text_color);
-                        let color = span.color.or(style.value).unwrap_or_else(|| Color::BLACK);
+    /// Scrolls the editor when drag selecting past its visible edges, or when
+    /// the caret was moved out of view.
+    fn auto_scroll(
+        &self,
+        state: &mut State<Link, H, Renderer::Paragraph>,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        viewport: &Rectangle,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        if !state.is_focused() {
+            state.scroll_to_caret = false;
+            return;
+        }
 
-                        let baseline =
-                            translation + Vector::new(0.0, size.0 + (line_height.0 - size.0) / 2.0);
+        let publisher = &self.publisher();
 
-                        if span.underline || is_hovered_link {
-                            for bounds in &regions {
-                                renderer.fill_quad(
-                                    renderer::Quad {
-                                        bounds: Rectangle::new(
-                                            bounds.position() + baseline
-                                                - Vector::new(0.0, size.0 * 0.08),
-                                            Size::new(bounds.width, 1.0),
-                                        ),
-                                        ..Default::default()
-                                    },
-                                    color,
-                                );
-                            }
-                        }
+        let padding = self.text_padding(layout);
 
-                        if span.strikethrough {
+        let Some(visible_bounds) = layout.bounds().shrink(padding).intersection(viewport) else {
+            return;
+        };
+
+        let line_height = self.line_height_in_pixels(renderer);
+
+        let translation = layout.position() - Point::ORIGIN;
+
+        if state.drag_click.is_some()
+            && state.drag_source.is_none()
+            && let Some(position) = cursor.position()
+        {
+            let lines = if position.y < visible_bounds.y {
+                -1
+            } else if position.y > visible_bounds.y + visible_bounds.height {
+                1
+            } else {
+                0
+            };
+
+            if lines != 0 {
+                let target = Point::new(
+                    position
+                        .x
+                        .clamp(visible_bounds.x, visible_bounds.x + visible_bounds.width),
+                    position
+                        .y
+                        .clamp(visible_bounds.y, visible_bounds.y + visible_bounds.height),
+                ) - translation
+                    - Vector::new(padding.left, padding.top);
+
+                if self.scrollable {
+                    self.scroll_by(state, layout, lines as f32 * line_height);
+                } else {
+                    publisher.publish(shell, Action::Scroll { lines });
+                }
+
+                self.publish_pointer(
+                    Action::Drag(target + Vector::new(state.scroll_offset_x, state.scroll_offset)),
+                    state,
+                    renderer,
+                    publisher,
+                    shell,
+                );
+                shell.request_redraw_at(window::RedrawRequest::At(
+                    Instant::now() + AUTO_SCROLL_INTERVAL,
+                ));
+            }
+        }
+
+        if std::mem::take(&mut state.scroll_to_caret) {
+            let caret = caret_position(self.content.0.borrow().editor.cursor())
+                + translation
+                + INSET_VECTOR
+                - Vector::new(0.0, state.scroll_offset);
+
+            let overflow = if caret.y < visible_bounds.y {
+                caret.y - visible_bounds.y
+            } else {
+                (caret.y + line_height - visible_bounds.y - visible_bounds.height).max(0.0)
+            };
+
+            if self.scrollable {
+                self.scroll_by(state, layout, overflow);
+            } else {
+                let lines = (overflow / line_height).abs().ceil().copysign(overflow) as i32;
+
+                if lines != 0 {
+                    publisher.publish(shell, Action::Scroll { lines });
+                }
+            }
+        }
+    }
+
+    /// Lays out the editor of the [`TextBox`], following the pattern of
+    /// `text_editor`.
+    fn layout_editor(
+        &self,
+        editor: &mut Renderer::Editor,
+        limits: &layout::Limits,
+        font: Renderer::Font,
+        text_size: Pixels,
+        wrapping: Wrapping,
+        highlighter: &mut H,
+    ) -> layout::Node {
+        let padding = self.padding;
+        let line_height = self.line_height;
+
+        // First make the editor infinitely tall so that we can measure how much the
+        // text would take up with `editor.min_bounds()` We need this because the
+        // LayoutRuns used by `text::measure` to calculate the size of the content
+        // will be limited to the lines that fit the editor's bounds.
+        editor.update(
+            Size::new(editor.bounds().width, f32::INFINITY),
+            font,
+            text_size,
+            line_height,
+            wrapping,
+            highlighter,
+        );
+
+        // Get the bounds needed to fit this whole text
+        let min_bounds = editor.min_bounds();
+
+        // Resize to either the layout limits or whatever we need for the editor
+        let node = layout::Node::new(Size::new(
+            limits.max().width + padding.horizontal(),
+            limits
+                .max()
+                .height
+                .max(min_bounds.height + padding.vertical()),
+        ));
+
+        // Update editor with the final computed size
+        editor.update(
+            node.size(),
+            font,
+            text_size,
+            line_height,
+            wrapping,
+            highlighter,
+        );
+
+        node
+    }
+}
+
+/// The bounds of the scrollbar of a scrollable [`TextBox`].
+#[derive(Debug, Clone, Copy)]
+struct Scrollbar {
+    rail: Rectangle,
+    scroller: Rectangle,
+}
+
+/// Returns the position of the caret, or the start of the selection.
+fn caret_position(cursor: Cursor) -> Point {
+    match cursor {
+        Cursor::Caret(position) => position,
+        Cursor::Selection(ranges) => ranges.first().cloned().unwrap_or_default().position(),
+    }
+}
+
+/// Returns the width of the given character, for drawing a block caret over it.
+fn char_width<P: Paragraph>(
+    character: char,
+    size: Pixels,
+    line_height: LineHeight,
+    font: P::Font,
+) -> f32 {
+    text_width::<P>(character.encode_utf8(&mut [0; 4]), size, line_height, font)
+}
+
+/// Returns the width of the given single line of text.
+fn text_width<P: Paragraph>(
+    content: &str,
+    size: Pixels,
+    line_height: LineHeight,
+    font: P::Font,
+) -> f32 {
+    P::with_text(Text {
+        content,
+        bounds: Size::new(f32::INFINITY, f32::INFINITY),
+        size,
+        line_height,
+        font,
+        align_x: text::Alignment::Left,
+        align_y: alignment::Vertical::Top,
+        shaping: Shaping::Advanced,
+        wrapping: Wrapping::None,
+    })
+    .min_width()
+}
+
+/// Returns the horizontal offset of the given byte `index` of some pre-edit
+/// `content` laid out in `paragraph`.
+fn preedit_x<P: Paragraph>(paragraph: &P, content: &str, index: usize) -> f32 {
+    let graphemes = content
+        .get(..index)
+        .map_or(0, |content| content.graphemes(true).count());
+
+    paragraph
+        .grapheme_position(0, graphemes)
+        .map_or(paragraph.min_width(), |position| position.x)
+}
+
+pub struct Content<R = iced::Renderer>(RefCell<Internal<R>>)
+where
+    R: text::Renderer;
+
+/// The [`Content`] of a [`TextBox`], either borrowed from the application or
+/// shared with it.
+enum Handle<'a, R>
+where
+    R: text::Renderer,
+{
+    Borrowed(&'a Content<R>),
+    Shared(Rc<Content<R>>),
+}
+
+impl<R> std::ops::Deref for Handle<'_, R>
+where
+    R: text::Renderer,
+{
+    type Target = Content<R>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Borrowed(content) => content,
+            Self::Shared(content) => content,
+        }
+    }
+}
+
+struct Internal<R>
+where
+    R: text::Renderer,
+{
+    editor: R::Editor,
+    is_dirty: bool,
+    validator: Option<Rc<Validator>>,
+    error: Option<String>,
+    /// The version of the text when it was last validated.
+    validated: Option<u64>,
+    version: u64,
+    changes: Option<Vec<Change>>,
+    stats: Option<stats::Tracker>,
+    /// The actions published for the [`Content`] that were not performed
+    /// yet, in order.
+    published: VecDeque<Action>,
+    /// Where the selection started, if any.
+    anchor: Option<selection::Anchor>,
+    edits: debounce::Edits,
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Creates an empty [`Content`].
+    pub fn new() -> Self {
+        Self::with_text("")
+    }
+
+    /// Creates a [`Content`] with the given text.
+    pub fn with_text(text: &str) -> Self {
+        Self(RefCell::new(Internal {
+            editor: R::Editor::with_text(text),
+            is_dirty: true,
+            validator: None,
+            error: None,
+            validated: None,
+            version: 0,
+            changes: None,
+            stats: None,
+            published: VecDeque::new(),
+            anchor: None,
+            edits: debounce::Edits::new(),
+        }))
+    }
+
+    /// Performs an [`Action`] on the [`Content`].
+    pub fn perform(&mut self, action: Action) {
+        // Only the actions published by a text box are its own edits, and the
+        // ones published before them were dropped by the application
+        let is_own = {
+            let published = &mut self.0.get_mut().published;
+
+            match published.iter().position(|published| *published == action) {
+                Some(index) => {
+                    let _ = published.drain(..=index);
+                    true
+                }
+                None => false,
+            }
+        };
+
+        self.apply(action, is_own);
+    }
+
+    /// Performs an [`Action`] on the shared [`Content`], for a [`TextBox`]
+    /// that performs its own actions.
+    ///
+    /// Only the edits of the [`TextBox`] itself are published through its
+    /// debounced and throttled closures.
+    fn apply(&self, action: Action, is_own: bool) {
+        let is_edit = action.is_edit();
+
+        // The change is only described if someone listens to it
+        let pending = match &action {
+            Action::Edit(_) if self.is_tracking_changes() => {
+                let selection = self.selection_bounds();
+                let internal = self.0.borrow();
+
+                Some(change::Pending::new(
+                    internal.editor.cursor_position(),
+                    selection,
+                    internal.editor.line_count(),
+                    |index| internal.editor.line(index),
+                ))
+            }
+            _ => None,
+        };
+
+        let mut internal = self.0.borrow_mut();
+
+        let caret = internal.editor.cursor_position();
+        let anchor = selection::Anchor::after(internal.anchor, &action, caret);
+
+        internal.editor.perform(action);
+        internal.is_dirty = true;
+
+        // The editor drops the selections that end where they start
+        internal.anchor =
+            anchor.filter(|_| matches!(internal.editor.cursor(), Cursor::Selection(_)));
+
+        if is_edit {
+            internal.version += 1;
+
+            if is_own {
+                internal.edits.increment();
+            }
+        }
+
+        let change = pending.and_then(|pending| {
+            pending.finish(internal.version, internal.editor.line_count(), |index| {
+                internal.editor.line(index)
+            })
+        });
+
+        if let Some(change) = change {
+            let internal = &mut *internal;
+
+            if let Some(stats) = &mut internal.stats {
+                stats.update(&change, |index| internal.editor.line(index));
+            }
+
+            if let Some(changes) = &mut internal.changes {
+                changes.push(change);
+            }
+        }
+
+        drop(internal);
+
+        if is_edit {
+            self.validate();
+        }
+    }
+
+    /// Returns true if the [`Change`]s of the [`Content`] are kept or
+    /// counted.
+    fn is_tracking_changes(&self) -> bool {
+        let internal = self.0.borrow();
+
+        internal.changes.is_some() || internal.stats.is_some()
+    }
+
+    /// Returns true if every [`Action`] published for the [`Content`] was
+    /// performed, so its text and cursor are up to date.
+    fn is_settled(&self) -> bool {
+        self.0.borrow().published.is_empty()
+    }
+
+    /// Notes that every [`Action`] published for the [`Content`] was handled,
+    /// even if some of them were not performed.
+    fn settle(&self) {
+        self.0.borrow_mut().published.clear();
+    }
+
+    /// Returns the amount of lines of the [`Content`].
+    pub fn line_count(&self) -> usize {
+        self.0.borrow().editor.line_count()
+    }
+
+    /// Returns the text of the line at the given index, if it exists.
+    pub fn line(&self, index: usize) -> Option<Line<'_>> {
+        let internal = self.0.borrow();
+        let line = internal.editor.line(index)?;
+
+        Some(Line {
+            text: Cow::Owned(line.text.into_owned()),
+            ending: line.ending,
+        })
+    }
+
+    /// Returns an iterator of the text of the lines in the [`Content`].
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        (0..)
+            .map(|i| self.line(i))
+            .take_while(Option::is_some)
+            .flatten()
+    }
+
+    /// Returns the text of the [`Content`].
+    pub fn text(&self) -> String {
+        let mut contents = String::new();
+        let mut lines = self.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            contents.push_str(&line.text);
+
+            if lines.peek().is_some() {
+                contents.push_str(if line.ending == LineEnding::None {
+                    LineEnding::default().as_str()
+                } else {
+                    line.ending.as_str()
+                });
+            }
+        }
+
+        contents
+    }
+
+    /// Returns the kind of [`LineEnding`] used for separating lines in the [`Content`].
+    pub fn line_ending(&self) -> Option<LineEnding> {
+        Some(self.line(0)?.ending)
+    }
+
+    /// Returns the selected text of the [`Content`].
+    pub fn selection(&self) -> Option<String> {
+        self.0.borrow().editor.selection()
+    }
+
+    /// Returns the current cursor position of the [`Content`].
+    pub fn cursor_position(&self) -> (usize, usize) {
+        self.0.borrow().editor.cursor_position()
+    }
+
+    /// Returns whether the [`Content`] passed its last validation.
+    pub fn is_valid(&self) -> bool {
+        self.0.borrow().error.is_none()
+    }
+
+    /// Returns the error message of the last validation of the [`Content`],
+    /// if it failed.
+    pub fn error(&self) -> Option<String> {
+        self.0.borrow().error.clone()
+    }
+
+    /// Sets the function that validates the text of the [`Content`] after
+    /// every edit, and validates it right away.
+    fn set_validator(&self, validator: Rc<Validator>) {
+        self.0.borrow_mut().validator = Some(validator);
+        self.validate();
+    }
+
+    /// Validates the text of the [`Content`], unless it did not change since
+    /// its last validation.
+    fn validate(&self) {
+        let (validator, version) = {
+            let internal = self.0.borrow();
+
+            (internal.validator.clone(), internal.version)
+        };
+
+        let Some(validator) = validator else {
+            return;
+        };
+
+        if self.0.borrow().validated == Some(version) {
+            return;
+        }
+
+        let error = validator(&self.text()).err();
+        let mut internal = self.0.borrow_mut();
+
+        internal.error = error;
+        internal.validated = Some(version);
+    }
+}
+
+impl<Renderer> Default for Content<Renderer>
+where
+    Renderer: text::Renderer,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Renderer> std::fmt::Debug for Content<Renderer>
+where
+    Renderer: text::Renderer,
+    Renderer::Editor: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let internal = self.0.borrow();
+
+        f.debug_struct("Content")
+            .field("editor", &internal.editor)
+            .field("is_dirty", &internal.is_dirty)
+            .field("error", &internal.error)
+            .field("validated", &internal.validated)
+            .field("version", &internal.version)
+            .field("changes", &internal.changes)
+            .field("stats", &internal.stats)
+            .field("published", &internal.published)
+            .field("anchor", &internal.anchor)
+            .field("edits", &internal.edits)
+            .finish()
+    }
+}
+
+struct State<Link, H: highlighter::Highlighter, P: Paragraph> {
+    focus: Option<Focus>,
+    preedit: Option<input_method::Preedit>,
+    preedit_paragraph: P,
+    last_click: Option<mouse::Click>,
+    drag_click: Option<mouse::click::Kind>,
+    context_menu: Option<Point>,
+    drag_source: Option<drag::Source>,
+    kill_ring: kill_ring::KillRing,
+    recorder: recorder::Recorder,
+    is_secure: bool,
+    is_revealed: bool,
+    secure_paragraph: P,
+    chord: Option<chord::Pending>,
+    deferred: VecDeque<Step>,
+    debounce: debounce::Timer,
+    throttle: debounce::Timer,
+    primary_selection: Option<String>,
+    scroll_offset: f32,
+    scroll_offset_x: f32,
+    scroller_grabbed_at: Option<f32>,
+    scroll_to_caret: bool,
+    modifiers: keyboard::Modifiers,
+    partial_scroll: f32,
+    highlighter: RefCell<H>,
+    highlighter_settings: H::Settings,
+    highlighter_format_address: usize,
+    paragraph: P,
+    spans: Vec<Span<'static, Link, P::Font>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Focus {
+    updated_at: Instant,
+    now: Instant,
+    is_window_focused: bool,
+}
+
+impl Focus {
+    fn now() -> Self {
+        let now = Instant::now();
+
+        Self {
+            updated_at: now,
+            now,
+            is_window_focused: true,
+        }
+    }
+
+    fn is_cursor_visible(&self, blink: Option<Duration>) -> bool {
+        self.is_window_focused
+            && blink.is_none_or(|blink| {
+                ((self.now - self.updated_at).as_millis() / blink.as_millis().max(1))
+                    .is_multiple_of(2)
+            })
+    }
+}
+
+impl<Link, H: highlighter::Highlighter, P: Paragraph> operation::Focusable for State<Link, H, P> {
+    fn is_focused(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    fn focus(&mut self) {
+        self.focus = Some(Focus::now());
+    }
+
+    fn unfocus(&mut self) {
+        self.focus = None;
+        self.preedit = None;
+        self.context_menu = None;
+        self.chord = None;
+    }
+}
+
+// Layout function for background, taking maximum space after padding
+fn layout_background(limits: &layout::Limits, width: Length, height: Length) -> layout::Node {
+    layout::Node::new(limits.resolve(width, height, Size::ZERO))
+}
+
+impl<'a, Link, H, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for TextBox<'a, Link, H, Message, Theme, Renderer>
+where
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    Message: std::fmt::Debug + Clone + 'a,
+    Theme: Catalog,
+    Renderer: text::Renderer<Font = iced::Font> + geometry::Renderer + 'a,
+    Renderer::Paragraph: 'a,
+{
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let state = tree
+            .state
+            .downcast_mut::<State<Link, H, Renderer::Paragraph>>();
+
+        // Calculate content bounds with padding
+        let padding = self.padding.fit(Size::ZERO, limits.max());
+        let content_limits = limits.width(self.width).height(self.height).shrink(padding);
+
+        let text_size = self.text_size.unwrap_or_else(|| renderer.default_size());
+        let font = self.font.unwrap_or_else(|| renderer.default_font());
+
+        // Reserve some space for the icon, next to the text
+        let icon = self.icon.as_ref().map(|icon| {
+            let size = icon.size.unwrap_or(text_size);
+
+            let icon_size = Size::new(
+                char_width::<Renderer::Paragraph>(
+                    icon.code_point,
+                    size,
+                    self.line_height,
+                    icon.font,
+                ),
+                self.line_height.to_absolute(size).into(),
+            );
+
+            (icon, icon_size)
+        });
+
+        let (leading, trailing) = match icon {
+            Some((icon, size)) => match icon.side {
+                Side::Left => (size.width + icon.spacing, 0.0),
+                Side::Right => (0.0, size.width + icon.spacing),
+            },
+            None => (0.0, 0.0),
+        };
+
+        let content_limits = content_limits.shrink(Padding::ZERO.left(leading).right(trailing));
+
+        // A secure text box shows its masked text instead, in lines that never wrap
+        let masked = self.is_masked(state).then(|| {
+            let lines: Vec<String> = self
+                .content
+                .lines()
+                .map(|line| line.text.into_owned())
+                .collect();
+
+            secure::mask(&lines)
+        });
+
+        let wrapping = if self.secure || self.single_line {
+            Wrapping::None
+        } else {
+            self.wrapping
+        };
+
+        let masked_spans = masked
+            .iter()
+            .map(|masked| span(masked.as_str()))
+            .collect::<Vec<_>>();
+
+        // Layout each component
+        let mut spans_node = layout_spans::<Link, H, Renderer>(
+            state,
+            &content_limits,
+            self.width,
+            self.height,
+            Text {
+                content: if masked.is_some() {
+                    &masked_spans
+                } else {
+                    self.spans.as_ref().as_ref()
+                },
+                bounds: content_limits.max(),
+                size: text_size,
+                line_height: self.line_height,
+                font,
+                align_x: self.align_x,
+                align_y: self.align_y,
+                shaping: Shaping::Advanced,
+                wrapping,
+            },
+        );
+
+        if let Some(masked) = &masked
+            && state.is_focused()
+        {
+            state.secure_paragraph = Renderer::Paragraph::with_text(Text {
+                content: masked,
+                bounds: Size::new(f32::INFINITY, f32::INFINITY),
+                size: text_size,
+                line_height: self.line_height,
+                font,
+                align_x: text::Alignment::Left,
+                align_y: alignment::Vertical::Top,
+                shaping: Shaping::Advanced,
+                wrapping: Wrapping::None,
+            });
+        }
+
+        let background_node = layout_background(limits, self.width, self.height);
+
+        let mut internal = self.content.0.borrow_mut();
+
+        if state.highlighter_format_address != self.highlighter_format as usize {
+            state.highlighter.borrow_mut().change_line(0);
+
+            state.highlighter_format_address = self.highlighter_format as usize;
+        }
+
+        if state.highlighter_settings != self.highlighter_settings {
+            state
+                .highlighter
+                .borrow_mut()
+                .update(&self.highlighter_settings);
+
+            state.highlighter_settings = self.highlighter_settings.clone();
+        }
+
+        let editor_node = self.layout_editor(
+            &mut internal.editor,
+            &content_limits,
+            font,
+            text_size,
+            wrapping,
+            state.highlighter.borrow_mut().deref_mut(),
+        );
+
+        // Position the spans and editor nodes within the padded space
+        spans_node = spans_node
+            .align(
+                alignment::Alignment::Start,
+                alignment::Alignment::from(self.align_y),
+                content_limits.max(),
+            )
+            .move_to(Point::new(padding.left + leading, padding.top));
+
+        let editor_node = editor_node.move_to(Point::new(leading, 0.0));
+
+        // Use the maximum of content_bounds and editor's required size,
+        // but only if we're focused and not scrolling
+        let final_bounds = if self.scrollable {
+            let intrinsic_size = if state.is_focused() {
+                editor_node.size()
+            } else {
+                spans_node.size().expand(padding)
+            } + Size::new(leading + trailing, 0.0);
+
+            limits.resolve(self.width, self.height, intrinsic_size)
+        } else if state.is_focused() {
+            Size::new(
+                content_limits.max().width + leading + trailing,
+                content_limits.max().height.max(editor_node.size().height),
+            )
+            .expand(padding)
+        } else {
+            limits.resolve(
+                spans_node.size().width + padding.horizontal() + leading + trailing,
+                spans_node.size().height + padding.vertical(),
+                Size::ZERO,
+            )
+        };
+
+        let mut children = vec![spans_node, background_node, editor_node];
+
+        if let Some((icon, size)) = icon {
+            let x = match icon.side {
+                Side::Left => padding.left,
+                Side::Right => final_bounds.width - padding.right - size.width,
+            };
+
+            children.push(layout::Node::new(size).move_to(Point::new(x, padding.top)));
+        }
+
+        layout::Node::with_children(final_bounds, children)
+    }
+
+    fn diff(&self, _tree: &mut Tree) {
+        // The view is only rebuilt once the application handled every message,
+        // so the actions it did not perform by now were dropped
+        self.content.settle();
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State<Link, H, Renderer::Paragraph>>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::<Link, H, _> {
+            paragraph: Renderer::Paragraph::default(),
+            focus: None,
+            preedit: None,
+            preedit_paragraph: Renderer::Paragraph::default(),
+            last_click: None,
+            drag_click: None,
+            context_menu: None,
+            drag_source: None,
+            kill_ring: kill_ring::KillRing::default(),
+            recorder: recorder::Recorder::default(),
+            is_secure: false,
+            is_revealed: false,
+            secure_paragraph: Renderer::Paragraph::default(),
+            chord: None,
+            deferred: VecDeque::new(),
+            debounce: debounce::Timer::default(),
+            throttle: debounce::Timer::default(),
+            primary_selection: None,
+            scroll_offset: 0.0,
+            scroll_offset_x: 0.0,
+            scroller_grabbed_at: None,
+            scroll_to_caret: false,
+            modifiers: keyboard::Modifiers::default(),
+            partial_scroll: 0.0,
+            spans: Vec::new(),
+            highlighter: RefCell::new(H::new(&self.highlighter_settings)),
+            highlighter_settings: self.highlighter_settings.clone(),
+            highlighter_format_address: self.highlighter_format as usize,
+        })
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        defaults: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree
+            .state
+            .downcast_ref::<State<Link, H, Renderer::Paragraph>>();
+
+        let bounds = layout.bounds();
+
+        let is_mouse_over = cursor.is_over(bounds);
+
+        let status = if !self.content.is_valid() {
+            Status::Invalid
+        } else if state.focus.is_some() {
+            Status::Focused
+        } else if is_mouse_over {
+            Status::Hovered
+        } else {
+            Status::Active
+        };
+
+        let style = theme.style(&self.class, status);
+
+        let mut children_layout = layout.children();
+        let spans_layout = children_layout.next().unwrap();
+        let background_layout = children_layout.next().unwrap();
+        let editor_layout = children_layout.next().unwrap();
+
+        let translation = layout.position() - Point::ORIGIN;
+
+        let scroll_offset = self.scroll_offset(state, layout);
+
+        let hovered_span = cursor.position_in(layout.bounds()).and_then(|position| {
+            state
+                .paragraph
+                .hit_span(position + Vector::new(0.0, scroll_offset))
+        });
+
+        let text_bounds = if self.scrollable {
+            editor_layout.bounds()
+        } else {
+            layout.bounds()
+        };
+
+        let clip_bounds = if self.scrollable {
+            bounds.intersection(viewport).unwrap_or_default()
+        } else {
+            *viewport
+        };
+
+        let text_viewport = *viewport + Vector::new(0.0, scroll_offset);
+
+        if !state.is_focused() {
+            // Draw a stroke around the whole object
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: background_layout.bounds(),
+                    border: style.border,
+                    shadow: style.shadow,
+                    ..renderer::Quad::default()
+                },
+                style.background,
+            );
+
+            // The spans of a masked text box are replaced by its masked text
+            let spans = if self.is_masked(state) {
+                &[]
+            } else {
+                self.spans.as_ref().as_ref()
+            };
+
+            let draw_spans = |renderer: &mut Renderer| {
+                for (index, span) in spans.iter().enumerate() {
+                    let is_hovered_link = span.link.is_some() && Some(index) == hovered_span;
+
+                    if span.highlight.is_some()
+                        || span.underline
+                        || span.strikethrough
+                        || is_hovered_link
+                    {
+                        let regions = state.paragraph.span_bounds(index);
+
+                        if let Some(highlight) = span.highlight {
                             for bounds in &regions {
+                                let bounds = Rectangle::new(
+                                    bounds.position()
+                                        - Vector::new(span.padding.left, span.padding.top),
+                                    bounds.size()
+                                        + Size::new(
+                                            span.padding.horizontal(),
+                                            span.padding.vertical(),
+                                        ),
+                                );
+
                                 renderer.fill_quad(
                                     renderer::Quad {
-                                        bounds: Rectangle::new(
-                                            bounds.position() + baseline
-                                                - Vector::new(0.0, size.0 / 2.0),
-                                            Size::new(bounds.width, 1.0),
+                                        bounds: bounds + translation,
+                                        border: highlight.border,
+                                        ..Default::default()
+                                    },
+                                    highlight.background,
+                                );
+                            }
+                        }
+
+                        if span.underline || span.strikethrough || is_hovered_link {
+                            let size = span
+                                .size
+                                .or(self.text_size)
+                                .unwrap_or(renderer.default_size());
+
+                            let line_height = span
+                                .line_height
+                                .unwrap_or(self.line_height)
+                                .to_absolute(size);
+
+                            // let color = span.color.or(style.value).unwrap_or(defaults.text_color);
+                            let color = span.color.or(style.value).unwrap_or(Color::BLACK);
+
+                            let baseline = translation
+                                + Vector::new(0.0, size.0 + (line_height.0 - size.0) / 2.0);
+
+                            if span.underline || is_hovered_link {
+                                for bounds in &regions {
+                                    renderer.fill_quad(
+                                        renderer::Quad {
+                                            bounds: Rectangle::new(
+                                                bounds.position() + baseline
+                                                    - Vector::new(0.0, size.0 * 0.08),
+                                                Size::new(bounds.width, 1.0),
+                                            ),
+                                            ..Default::default()
+                                        },
+                                        color,
+                                    );
+                                }
+                            }
+
+                            if span.strikethrough {
+                                for bounds in &regions {
+                                    renderer.fill_quad(
+                                        renderer::Quad {
+                                            bounds: Rectangle::new(
+                                                bounds.position() + baseline
+                                                    - Vector::new(0.0, size.0 / 2.0),
+                                                Size::new(bounds.width, 1.0),
+                                            ),
+                                            ..Default::default()
+                                        },
+                                        color,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                draw_text(
+                    self.color.or(style.value),
+                    renderer,
+                    defaults,
+                    spans_layout,
+                    &state.paragraph,
+                    &text_viewport,
+                );
+            };
+
+            if self.scrollable {
+                renderer.with_layer(clip_bounds, |renderer| {
+                    renderer.with_translation(Vector::new(0.0, -scroll_offset), draw_spans);
+                });
+            } else {
+                draw_spans(renderer);
+            }
+        } else {
+            let is_masked = self.is_masked(state);
+            let masked_cursor = is_masked.then(|| self.masked_cursor(renderer));
+
+            let mut internal = self.content.0.borrow_mut();
+            let font = self.font.unwrap_or_else(|| renderer.default_font());
+            internal.editor.highlight(
+                font,
+                state.highlighter.borrow_mut().deref_mut(),
+                |highlight| (self.highlighter_format)(highlight, theme),
+            );
+
+            let inset = INSET_VECTOR;
+            let translation = editor_layout.position() - Point::ORIGIN;
+            let editor_rect = editor_layout.bounds().shrink(EDITOR_INSET);
+
+            let frame_height = if self.scrollable {
+                bounds.height
+            } else {
+                editor_layout
+                    .bounds()
+                    .height
+                    .max(background_layout.bounds().height)
+            };
+
+            let frame = Rectangle::new(layout.position(), Size::new(bounds.width, frame_height));
+
+            if style.focus_ring.width > 0.0 || style.focus_ring.shadow.color.a > 0.0 {
+                // Draw the focus ring around the frame, outside of the clip bounds
+                let spread = style.focus_ring.offset + style.focus_ring.width;
+                let radius = style.border.radius;
+
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: frame.expand(spread),
+                        border: Border {
+                            width: style.focus_ring.width,
+                            color: style.focus_ring.color,
+                            radius: border::Radius {
+                                top_left: radius.top_left + spread,
+                                top_right: radius.top_right + spread,
+                                bottom_right: radius.bottom_right + spread,
+                                bottom_left: radius.bottom_left + spread,
+                            },
+                        },
+                        shadow: style.focus_ring.shadow,
+                        ..renderer::Quad::default()
+                    },
+                    Color::TRANSPARENT,
+                );
+            }
+
+            renderer.with_layer(clip_bounds, |renderer| {
+                // Draw a stroke around the whole object
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: frame,
+                        border: style.border,
+                        shadow: style.shadow,
+                        ..renderer::Quad::default()
+                    },
+                    style.background,
+                );
+
+                let scroll_x = Vector::new(state.scroll_offset_x, 0.0);
+
+                renderer.with_translation(Vector::new(-scroll_x.x, -scroll_offset), |renderer| {
+                    if is_masked {
+                        renderer.fill_paragraph(
+                            &state.secure_paragraph,
+                            editor_rect.position(),
+                            style.value.unwrap_or(defaults.text_color),
+                            editor_rect + scroll_x,
+                        );
+                    } else {
+                        renderer.fill_editor(
+                            &internal.editor,
+                            editor_rect.position(),
+                            style.value.unwrap_or(defaults.text_color),
+                            editor_rect + scroll_x,
+                        );
+                    }
+
+                    if let Some(mask) = &self.input_mask
+                        && let Some(line) = internal.editor.line(0)
+                    {
+                        // Show the rest of the mask after the text
+                        let text_size = self.text_size.unwrap_or_else(|| renderer.default_size());
+                        let placeholder = mask.placeholder(line.text.chars().count());
+
+                        let x = text_width::<Renderer::Paragraph>(
+                            &line.text,
+                            text_size,
+                            self.line_height,
+                            font,
+                        );
+
+                        renderer.fill_text(
+                            Text {
+                                content: placeholder,
+                                bounds: editor_rect.size(),
+                                size: text_size,
+                                line_height: self.line_height,
+                                font,
+                                align_x: text::Alignment::Left,
+                                align_y: alignment::Vertical::Top,
+                                shaping: Shaping::Advanced,
+                                wrapping: Wrapping::None,
+                            },
+                            editor_rect.position() + Vector::new(x, 0.0),
+                            style.placeholder,
+                            editor_rect,
+                        );
+                    }
+
+                    let editor_cursor = masked_cursor.unwrap_or_else(|| internal.editor.cursor());
+                    let caret = caret_position(editor_cursor.clone());
+
+                    if let Some(focus) = state.focus.as_ref() {
+                        match editor_cursor {
+                            Cursor::Caret(position)
+                                if focus.is_cursor_visible(self.caret.blink) =>
+                            {
+                                let text_size =
+                                    self.text_size.unwrap_or_else(|| renderer.default_size());
+                                let line_height: f32 =
+                                    self.line_height.to_absolute(text_size).into();
+
+                                let caret_shape = self
+                                    .keymap
+                                    .and_then(|keymap| keymap.caret_shape())
+                                    .unwrap_or(self.caret.shape);
+
+                                let character_width = || {
+                                    let (line, column) = internal.editor.cursor_position();
+
+                                    let character = internal
+                                        .editor
+                                        .line(line)
+                                        .and_then(|line| line.text.get(column..)?.chars().next())
+                                        .map(
+                                            |character| {
+                                                if is_masked { secure::MASK } else { character }
+                                            },
+                                        )
+                                        .unwrap_or(' ');
+
+                                    char_width::<Renderer::Paragraph>(
+                                        character,
+                                        text_size,
+                                        self.line_height,
+                                        font,
+                                    )
+                                };
+
+                                let (cursor, color) = match caret_shape {
+                                    CaretShape::Bar => (
+                                        Rectangle::new(
+                                            position,
+                                            Size::new(self.caret.width, line_height),
                                         ),
-                                        ..Default::default()
-                                    },
-                                    color,
-                                );
+                                        style.caret,
+                                    ),
+                                    CaretShape::Block => (
+                                        Rectangle::new(
+                                            position,
+                                            Size::new(character_width(), line_height),
+                                        ),
+                                        // Keep the character under the block readable
+                                        style.caret.scale_alpha(0.4),
+                                    ),
+                                    CaretShape::Underline => (
+                                        Rectangle::new(
+                                            position
+                                                + Vector::new(0.0, line_height - self.caret.width),
+                                            Size::new(character_width(), self.caret.width),
+                                        ),
+                                        style.caret,
+                                    ),
+                                };
+
+                                let cursor = cursor + translation;
+
+                                if let Some(clipped_cursor) =
+                                    (editor_layout.bounds() + scroll_x).intersection(&cursor)
+                                {
+                                    renderer.fill_quad(
+                                        renderer::Quad {
+                                            bounds: clipped_cursor + inset,
+                                            ..renderer::Quad::default()
+                                        },
+                                        color,
+                                    );
+                                }
+                            }
+                            Cursor::Selection(ranges) => {
+                                for range in ranges.into_iter().filter_map(|range| {
+                                    (text_bounds + scroll_x).intersection(&(range + translation))
+                                }) {
+                                    renderer.fill_quad(
+                                        renderer::Quad {
+                                            bounds: range + inset,
+                                            ..renderer::Quad::default()
+                                        },
+                                        style.selection,
+                                    );
+                                }
                             }
+                            Cursor::Caret(_) => {}
+                        }
+
+                        if let Some(preedit) = state
+                            .preedit
+                            .as_ref()
+                            .filter(|preedit| !preedit.content.is_empty())
+                        {
+                            draw_preedit(
+                                renderer,
+                                preedit,
+                                &state.preedit_paragraph,
+                                caret + translation + inset,
+                                style.background,
+                                style.value.unwrap_or(defaults.text_color),
+                                editor_layout.bounds(),
+                            );
                         }
                     }
+                });
+
+                if let Some(pending) = &state.chord {
+                    // Show the keys of the pending chord at the top right corner
+                    let text_size = self.text_size.unwrap_or_else(|| renderer.default_size());
+                    let bounds = editor_layout.bounds().shrink(self.padding);
+
+                    renderer.fill_text(
+                        Text {
+                            content: pending.label(),
+                            bounds: bounds.size(),
+                            size: text_size * 0.85,
+                            line_height: self.line_height,
+                            font,
+                            align_x: text::Alignment::Right,
+                            align_y: alignment::Vertical::Top,
+                            shaping: Shaping::Advanced,
+                            wrapping: Wrapping::None,
+                        },
+                        Point::new(bounds.x + bounds.width, bounds.y),
+                        style.placeholder,
+                        bounds,
+                    );
                 }
-            }
-            draw_text(
-                self.color.or(style.value),
-                renderer,
-                defaults,
-                spans_layout,
-                &state.paragraph,
-                &viewport,
+            });
+        }
+
+        if let Some(icon) = &self.icon
+            && let Some(icon_layout) = layout.children().nth(3)
+        {
+            renderer.fill_text(
+                Text {
+                    content: icon.code_point.to_string(),
+                    bounds: icon_layout.bounds().size(),
+                    size: icon
+                        .size
+                        .or(self.text_size)
+                        .unwrap_or_else(|| renderer.default_size()),
+                    line_height: self.line_height,
+                    font: icon.font,
+                    align_x: text::Alignment::Left,
+                    align_y: alignment::Vertical::Top,
+                    shaping: Shaping::Advanced,
+                    wrapping: Wrapping::None,
+                },
+                icon_layout.position(),
+                style.icon,
+                *viewport,
             );
-        } else {
-            let mut internal = self.content.0.borrow_mut();
-            let font = self.font.unwrap_or_else(|| renderer.default_font());
-            internal.editor.highlight(
-                font,
-                state.highlighter.borrow_mut().deref_mut(),
-                |highlight| (self.highlighter_format)(highlight, theme),
+        }
+
+        if self.counter {
+            let counts = self.content.counts();
+            let bounds = layout.bounds().shrink(self.padding);
+
+            let label = match self.max_chars {
+                Some(max_chars) => format!("{} / {max_chars}", counts.chars),
+                None => counts.chars.to_string(),
+            };
+
+            renderer.fill_text(
+                Text {
+                    content: label,
+                    bounds: bounds.size(),
+                    size: self.text_size.unwrap_or_else(|| renderer.default_size()) * 0.85,
+                    line_height: self.line_height,
+                    font: self.font.unwrap_or_else(|| renderer.default_font()),
+                    align_x: text::Alignment::Right,
+                    align_y: alignment::Vertical::Bottom,
+                    shaping: Shaping::Advanced,
+                    wrapping: Wrapping::None,
+                },
+                Point::new(bounds.x + bounds.width, bounds.y + bounds.height),
+                style.counter,
+                *viewport,
+            );
+        }
+
+        if let Some(scrollbar) = self.scrollbar(state, layout) {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scrollbar.rail,
+                    border: style.scrollbar.border,
+                    ..renderer::Quad::default()
+                },
+                style
+                    .scrollbar
+                    .background
+                    .unwrap_or(Color::TRANSPARENT.into()),
+            );
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scrollbar.scroller,
+                    border: Border {
+                        radius: style.scrollbar.border.radius,
+                        ..Border::default()
+                    },
+                    ..renderer::Quad::default()
+                },
+                style.scrollbar.scroller,
             );
+        }
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        let state = tree
+            .state
+            .downcast_mut::<State<Link, H, Renderer::Paragraph>>();
+
+        state.scroll_offset = self.scroll_offset(state, layout);
+        state.is_secure = self.secure;
+
+        // The edits performed by the application are only seen afterwards
+        if let Some(on_change) = &self.on_change {
+            for change in self.content.take_changes() {
+                shell.publish(on_change(change));
+            }
+        }
+
+        self.publish_delayed(state, shell);
+
+        match *event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = modifiers;
+            }
+            Event::Window(window::Event::Unfocused) => {
+                if let Some(focus) = &mut state.focus {
+                    focus.is_window_focused = false;
+                }
+            }
+            Event::Window(window::Event::Focused) => {
+                if let Some(focus) = &mut state.focus {
+                    focus.is_window_focused = true;
+                    focus.updated_at = Instant::now();
+
+                    shell.request_redraw();
+                    shell.invalidate_layout();
+                }
+            }
+            Event::Window(window::Event::RedrawRequested(now)) => {
+                if let Some(focus) = &mut state.focus
+                    && focus.is_window_focused
+                {
+                    focus.now = now;
+
+                    if let Some(blink) = self.caret.blink {
+                        let interval = blink.as_millis().max(1);
+                        let millis_until_redraw =
+                            interval - (now - focus.updated_at).as_millis() % interval;
+
+                        shell.request_redraw_at(window::RedrawRequest::At(
+                            now + Duration::from_millis(millis_until_redraw as u64),
+                        ));
+                    }
+                }
+
+                shell.request_input_method(&self.input_method(state, renderer, layout));
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if state.drag_click.is_some() => {
+                // Keep auto scrolling if the drag leaves the visible bounds
+                shell.request_redraw();
+            }
+            _ => {}
+        }
+
+        if self.is_icon_pressable()
+            && let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
+            && layout
+                .children()
+                .nth(3)
+                .is_some_and(|icon_layout| cursor.is_over(icon_layout.bounds()))
+        {
+            if self.secure && self.revealable {
+                state.is_revealed = !state.is_revealed;
+                shell.invalidate_layout();
+                shell.request_redraw();
+            }
+
+            if let Some(on_icon_press) = &self.on_icon_press {
+                shell.publish(on_icon_press.clone());
+            }
+
+            shell.capture_event();
+            return;
+        }
+
+        if self.scrollable && self.update_scroll(state, event, layout, cursor, renderer) {
+            shell.capture_event();
+            shell.request_redraw();
+            return;
+        }
+
+        if self.on_edit.is_none() && !self.performs_actions() {
+            return;
+        }
+
+        let target = &self.target();
+        let publisher = &target.publisher;
+
+        resume(target, state, clipboard, shell);
+
+        if let Event::Window(window::Event::RedrawRequested(_)) = *event {
+            self.auto_scroll(state, layout, cursor, renderer, viewport, shell);
+
+            self.follow_caret(state, layout, renderer);
+
+            if self.primary_selection && !self.secure && state.is_focused() {
+                let selection = self.content.selection();
+
+                if selection != state.primary_selection {
+                    if let Some(selection) = &selection {
+                        clipboard.write(clipboard::Kind::Primary, selection.clone());
+                    }
+
+                    state.primary_selection = selection;
+                }
+            }
+        }
+
+        // The selection was dragged away, so we wait until it is either dropped
+        // by some other text box or discarded
+        if state
+            .drag_source
+            .as_ref()
+            .is_some_and(|source| source.is_released)
+        {
+            state.drag_source = None;
+
+            if drag::finish().is_some_and(|payload| payload.is_moved()) {
+                publisher.publish(shell, Action::Edit(Edit::Delete));
+            }
+        }
+
+        if let Event::Window(window::Event::RedrawRequested(now)) = *event
+            && let Some(key_presses) = chord::Pending::expire(&mut state.chord, now)
+        {
+            self.replay(key_presses, state, target, clipboard, shell);
+        }
+
+        if let Event::Keyboard(keyboard::Event::KeyPressed {
+            key,
+            modifiers,
+            text,
+            ..
+        }) = event
+            && state.is_focused()
+            && !self.chords.is_empty()
+        {
+            let key_press = KeyPress {
+                key: key.clone(),
+                modifiers: *modifiers,
+                text: text.clone(),
+                status: Status::Focused,
+            };
+
+            let resolution =
+                chord::resolve(&self.chords, &mut state.chord, key_press, Instant::now());
+
+            if self.handle_chord(resolution, state, target, clipboard, shell) {
+                return;
+            }
+        }
+
+        let Some(update) = Update::from_event::<Link, H, Renderer>(
+            event,
+            state,
+            layout.bounds(),
+            self.text_padding(layout),
+            cursor,
+            self.key_binding.as_deref(),
+        ) else {
+            return;
+        };
+
+        match update {
+            Update::Click(click) => match click.kind() {
+                mouse::click::Kind::Single => {
+                    state.last_click = Some(click);
+                    state.drag_click = Some(click.kind());
+
+                    let is_extending = state.modifiers.shift();
+
+                    let source = (state.is_focused() && !is_extending && !self.secure)
+                        .then(|| {
+                            let cursor = self.content.0.borrow().editor.cursor();
+
+                            drag::Source::grab(cursor, click.position())
+                        })
+                        .flatten();
+
+                    if let Some(source) = source {
+                        // Clicking on the selection starts dragging it around
+                        drag::start(self.content.selection().unwrap_or_default());
+
+                        state.drag_source = Some(source);
+                        shell.capture_event();
+                    } else if state.is_focused() {
+                        shell.capture_event();
+
+                        if is_extending {
+                            self.publish_pointer(
+                                Action::Drag(click.position()),
+                                state,
+                                renderer,
+                                publisher,
+                                shell,
+                            );
+                        } else {
+                            self.publish_pointer(
+                                Action::Click(click.position()),
+                                state,
+                                renderer,
+                                publisher,
+                                shell,
+                            );
+                        }
+
+                        shell.request_redraw();
+                    }
+                }
+                mouse::click::Kind::Double => {
+                    state.last_click = Some(click);
+                    state.drag_click = Some(click.kind());
+                    if state.is_focused() && self.secure {
+                        // Selecting words would reveal where they are
+                        publisher.publish(shell, Action::SelectAll);
+                        shell.capture_event();
+                        shell.request_redraw();
+                    } else if state.is_focused() && state.modifiers.shift() {
+                        // Snap the extended end of the selection to the word boundary
+                        publisher.publish(
+                            shell,
+                            Action::Select(if self.content.is_selecting_backwards() {
+                                Motion::WordLeft
+                            } else {
+                                Motion::WordRight
+                            }),
+                        );
+                        shell.capture_event();
+                        shell.request_redraw();
+                    } else if state.is_focused() {
+                        publisher.publish(shell, Action::SelectWord);
+                        shell.capture_event();
+                        shell.request_redraw();
+                    } else {
+                        state.focus();
+                        shell.invalidate_layout();
+                        self.publish_pointer(
+                            Action::Click(click.position()),
+                            state,
+                            renderer,
+                            publisher,
+                            shell,
+                        );
+                        shell.capture_event();
+                    }
+                }
+                mouse::click::Kind::Triple => {
+                    state.last_click = Some(click);
+                    state.drag_click = Some(click.kind());
+                    if state.is_focused() {
+                        publisher.publish(shell, Action::SelectLine);
+                        shell.capture_event();
+                        shell.request_redraw();
+                    } else {
+                        state.focus();
+                        shell.invalidate_layout();
+                        self.publish_pointer(
+                            Action::Click(click.position()),
+                            state,
+                            renderer,
+                            publisher,
+                            shell,
+                        );
+                        shell.capture_event();
+                    }
+                }
+            },
+            Update::Drag(position) => {
+                shell.capture_event();
+
+                if state.drag_source.is_some() {
+                    shell.request_redraw();
+                } else if !(self.secure && state.drag_click == Some(mouse::click::Kind::Double)) {
+                    self.publish_pointer(Action::Drag(position), state, renderer, publisher, shell);
+                }
+            }
+            Update::Release(position) => {
+                state.drag_click = None;
+
+                if let Some(source) = &mut state.drag_source {
+                    let Some(position) = position else {
+                        source.is_released = true;
+                        return;
+                    };
+
+                    let payload = drag::finish();
+
+                    if source.contains(position) {
+                        // The selection was not dragged anywhere; just a click
+                        self.publish_pointer(
+                            Action::Click(position),
+                            state,
+                            renderer,
+                            publisher,
+                            shell,
+                        );
+                    } else if let Some(payload) = payload {
+                        let is_copy = drag::is_copy(state.modifiers);
+
+                        for action in source.drop(position, payload.text, is_copy) {
+                            publisher.publish(shell, action);
+                        }
+                    }
+
+                    state.drag_source = None;
+                    shell.capture_event();
+                    shell.request_redraw();
+                } else if let Some(position) = position {
+                    // Some other text box may be dropping its selection here
+                    let Some(text) = drag::drop(drag::is_copy(state.modifiers)) else {
+                        return;
+                    };
+
+                    if !state.is_focused() {
+                        state.focus();
+                        shell.invalidate_layout();
+                    }
+
+                    self.publish_pointer(
+                        Action::Click(position),
+                        state,
+                        renderer,
+                        publisher,
+                        shell,
+                    );
+
+                    state.deferred.push_back(Step::Insert(text));
+                    resume(target, state, clipboard, shell);
+
+                    shell.capture_event();
+                    shell.request_redraw();
+                }
+            }
+            Update::FileDrop(path, position) => {
+                let Some(on_drop) = &self.on_drop else {
+                    return;
+                };
+
+                if !state.is_focused() {
+                    state.focus();
+                    shell.invalidate_layout();
+                }
+
+                self.publish_pointer(Action::Click(position), state, renderer, publisher, shell);
+                shell.publish(on_drop(path));
+                shell.capture_event();
+            }
+            Update::ContextMenu(position) => {
+                if self.context_menu.is_none() {
+                    return;
+                }
+
+                // Right clicking an unfocused text box focuses it first
+                let is_selected = state.is_focused()
+                    && match self.content.0.borrow().editor.cursor() {
+                        Cursor::Selection(ranges) => {
+                            ranges.iter().any(|range| range.contains(position))
+                        }
+                        Cursor::Caret(_) => false,
+                    };
+
+                if !state.is_focused() {
+                    state.focus();
+                    shell.invalidate_layout();
+                }
+
+                // Right clicking outside of the selection selects the word below
+                if !is_selected {
+                    self.publish_pointer(
+                        Action::Click(position),
+                        state,
+                        renderer,
+                        publisher,
+                        shell,
+                    );
+                    publisher.publish(
+                        shell,
+                        if self.secure {
+                            Action::SelectAll
+                        } else {
+                            Action::SelectWord
+                        },
+                    );
+                }
+
+                state.drag_click = None;
+                state.context_menu = cursor.position();
+
+                shell.capture_event();
+                shell.request_redraw();
+            }
+            Update::PrimaryPaste(position) => {
+                if !self.primary_selection {
+                    return;
+                }
+
+                let Some(contents) = clipboard.read(clipboard::Kind::Primary) else {
+                    return;
+                };
+
+                if !state.is_focused() {
+                    state.focus();
+                    shell.invalidate_layout();
+                }
+
+                state.scroll_to_caret = true;
+
+                self.publish_pointer(Action::Click(position), state, renderer, publisher, shell);
+
+                state.deferred.push_back(Step::Insert(contents));
+                resume(target, state, clipboard, shell);
+
+                shell.capture_event();
+                shell.request_redraw();
+            }
+            Update::Scroll(lines) => {
+                let bounds = self.content.0.borrow().editor.bounds();
+
+                if bounds.height >= i32::MAX as f32 {
+                    return;
+                }
 
-            let inset = INSET_VECTOR;
-            let editor_rect = editor_layout.bounds().shrink(EDITOR_INSET);
+                let lines = lines + state.partial_scroll;
+                state.partial_scroll = lines.fract();
 
-            renderer.with_layer(*viewport, |renderer| {
-                // Draw a stroke around the whole object
-                renderer.fill_quad(
-                    renderer::Quad {
-                        bounds: Rectangle::new(
-                            editor_layout.position(),
-                            Size::new(
-                                editor_layout.bounds().width,
-                                editor_layout
-                                    .bounds()
-                                    .height
-                                    .max(background_layout.bounds().height),
-                            ),
-                        )
-                        .shrink(1.0),
-                        border: Border {
-                            width: 1.0,
-                            color: Color::BLACK.scale_alpha(0.8),
-                            radius: 0.0.into(),
-                        },
-                        ..renderer::Quad::default()
+                publisher.publish(
+                    shell,
+                    Action::Scroll {
+                        lines: lines as i32,
                     },
-                    style.background,
-                );
-
-                renderer.fill_editor(
-                    &internal.editor,
-                    editor_rect.position(),
-                    style.value.unwrap_or(defaults.text_color),
-                    editor_rect,
                 );
+            }
+            Update::InputMethod(update) => match update {
+                Ime::Toggle(is_open) => {
+                    state.preedit = is_open.then(input_method::Preedit::new);
 
-                if let Some(focus) = state.focus.as_ref() {
-                    match internal.editor.cursor() {
-                        Cursor::Caret(position) if focus.is_cursor_visible() => {
-                            let cursor = Rectangle::new(
-                                position + translation,
-                                Size::new(
-                                    1.0,
-                                    self.line_height
-                                        .to_absolute(
-                                            self.text_size
-                                                .unwrap_or_else(|| renderer.default_size()),
-                                        )
-                                        .into(),
-                                ),
-                            );
+                    shell.request_redraw();
+                }
+                Ime::Preedit { content, selection } => {
+                    state.preedit_paragraph = Renderer::Paragraph::with_text(Text {
+                        content: content.as_str(),
+                        bounds: Size::new(f32::INFINITY, f32::INFINITY),
+                        size: self.text_size.unwrap_or_else(|| renderer.default_size()),
+                        line_height: self.line_height,
+                        font: self.font.unwrap_or_else(|| renderer.default_font()),
+                        align_x: text::Alignment::Left,
+                        align_y: alignment::Vertical::Top,
+                        shaping: Shaping::Advanced,
+                        wrapping: Wrapping::None,
+                    });
+
+                    state.preedit = Some(input_method::Preedit {
+                        content,
+                        selection,
+                        text_size: self.text_size,
+                    });
+
+                    shell.capture_event();
+                    shell.request_redraw();
+                }
+                Ime::Commit(text) => {
+                    if let Some(preedit) = &mut state.preedit {
+                        preedit.content.clear();
+                    }
 
-                            if let Some(clipped_cursor) =
-                                editor_layout.bounds().intersection(&cursor)
-                            {
-                                renderer.fill_quad(
-                                    renderer::Quad {
-                                        bounds: clipped_cursor + inset,
-                                        ..renderer::Quad::default()
-                                    },
-                                    style.value.unwrap_or(defaults.text_color),
-                                );
-                            }
-                        }
-                        Cursor::Selection(ranges) => {
-                            for range in ranges.into_iter().filter_map(|range| {
-                                text_bounds.intersection(&(range + translation))
-                            }) {
-                                renderer.fill_quad(
-                                    renderer::Quad {
-                                        bounds: range + inset,
-                                        ..renderer::Quad::default()
-                                    },
-                                    style.selection,
-                                );
-                            }
-                        }
-                        Cursor::Caret(_) => {}
+                    if let Some(focus) = &mut state.focus {
+                        focus.updated_at = Instant::now();
                     }
+
+                    state.scroll_to_caret = true;
+
+                    state.deferred.push_back(Step::Insert(text));
+                    resume(target, state, clipboard, shell);
+
+                    shell.capture_event();
+                    shell.request_redraw();
                 }
-            });
+            },
+            Update::Binding(binding) => {
+                handle_binding(binding, target, state, clipboard, shell);
+            }
         }
     }
 
-    fn update(
-        &mut self,
-        tree: &mut Tree,
-        event: &Event,
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        _viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let state = tree
+            .state
+            .downcast_mut::<State<Link, H, Renderer::Paragraph>>();
+
+        let Some(position) = state.context_menu else {
+            let message = self
+                .content
+                .error()
+                .filter(|message| state.is_focused() && !message.is_empty())?;
+
+            let bounds = layout.bounds();
+
+            return Some(overlay::Element::new(Box::new(validation::Overlay {
+                message,
+                class: &self.class,
+                position: Point::new(bounds.x, bounds.y + bounds.height) + translation,
+                width: bounds.width,
+                text_size: self.text_size.unwrap_or_else(|| renderer.default_size()),
+                line_height: self.line_height,
+                font: self.font.unwrap_or_else(|| renderer.default_font()),
+            })));
+        };
+        let entries = self.context_menu.as_deref()?;
+
+        if self.on_edit.is_none() && !self.performs_actions() {
+            return None;
+        }
+
+        Some(overlay::Element::new(Box::new(context_menu::Overlay {
+            state,
+            entries,
+            has_selection: self.content.selection().is_some(),
+            target: self.target(),
+            class: &self.class,
+            position: position + translation,
+            text_size: self.text_size.unwrap_or_else(|| renderer.default_size()),
+            line_height: self.line_height,
+            font: self.font.unwrap_or_else(|| renderer.default_font()),
+        })))
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Tree,
         layout: Layout<'_>,
         cursor: mouse::Cursor,
-        _renderer: &Renderer,
-        clipboard: &mut dyn Clipboard,
-        shell: &mut Shell<'_, Message>,
         _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = state
+            .state
+            .downcast_ref::<State<Link, H, Renderer::Paragraph>>();
+
+        if state.drag_source.is_some() || (drag::is_active() && cursor.is_over(layout.bounds())) {
+            mouse::Interaction::Grabbing
+        } else if self.is_icon_pressable()
+            && layout
+                .children()
+                .nth(3)
+                .is_some_and(|icon_layout| cursor.is_over(icon_layout.bounds()))
+        {
+            mouse::Interaction::Pointer
+        } else if cursor.is_over(layout.bounds()) && state.is_focused() {
+            mouse::Interaction::Text
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        _renderer: &Renderer,
+        operation: &mut dyn Operation,
     ) {
         let state = tree
             .state
             .downcast_mut::<State<Link, H, Renderer::Paragraph>>();
 
-        match *event {
-            Event::Window(window::Event::Unfocused) => {
-                if let Some(focus) = &mut state.focus {
-                    focus.is_window_focused = false;
+        operation.focusable(self.id.as_ref(), layout.bounds(), state);
+    }
+}
+
+/// Publishes the [`Action`]s of a [`TextBox`], performing them on its
+/// [`Content`] first if the [`TextBox`] performs its own actions.
+struct Publisher<'b, R, Message>
+where
+    R: text::Renderer,
+{
+    content: &'b Content<R>,
+    on_edit: Option<&'b dyn Fn(Action) -> Message>,
+    on_change: Option<&'b dyn Fn(Change) -> Message>,
+    perform: bool,
+}
+
+impl<R, Message> Publisher<'_, R, Message>
+where
+    R: text::Renderer,
+{
+    /// Publishes the given [`Action`], producing the message of
+    /// [`TextBox::on_action`] if any.
+    ///
+    /// The [`Change`] of a performed edit is published right after it.
+    fn publish(&self, shell: &mut Shell<'_, Message>, action: Action) {
+        if self.perform {
+            self.content.apply(action.clone(), true);
+
+            if let Some(on_change) = self.on_change {
+                for change in self.content.take_changes() {
+                    shell.publish(on_change(change));
+                }
+            }
+        }
+
+        if let Some(on_edit) = self.on_edit {
+            if !self.perform {
+                self.content
+                    .0
+                    .borrow_mut()
+                    .published
+                    .push_back(action.clone());
+            }
+
+            shell.publish(on_edit(action));
+        }
+    }
+}
+
+/// The [`Content`] of a [`TextBox`] and everything needed to apply a
+/// [`Binding`] to it.
+struct Target<'b, R, Message>
+where
+    R: text::Renderer,
+{
+    content: &'b Content<R>,
+    constraints: Constraints<'b>,
+    publisher: Publisher<'b, R, Message>,
+    on_submit: &'b Option<Message>,
+    on_blur: &'b Option<Message>,
+    on_record: Option<&'b OnRecord<'b, Message>>,
+    debounced: Option<&'b debounce::Delayed<'b, Message>>,
+    throttled: Option<&'b debounce::Delayed<'b, Message>>,
+}
+
+/// Handles a [`Binding`] produced by some key presses or a context menu entry.
+fn handle_binding<
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    R: text::Renderer,
+    Message: std::fmt::Debug + Clone,
+>(
+    binding: Binding<Message>,
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    clipboard: &mut dyn Clipboard,
+    shell: &mut Shell<'_, Message>,
+) {
+    state.scroll_to_caret |= binding.moves_caret();
+    state.kill_ring.interrupt(&binding);
+    state.recorder.record(&binding);
+
+    let was_recording = state.recorder.is_recording();
+
+    enqueue(binding, target, state, clipboard, shell);
+
+    if was_recording
+        && !state.recorder.is_recording()
+        && let Some(on_record) = target.on_record
+    {
+        shell.publish(on_record(state.recorder.recorded()));
+    }
+
+    if let Some(focus) = &mut state.focus {
+        focus.updated_at = Instant::now();
+    }
+
+    // Stateful keymaps may change the caret without editing
+    shell.request_redraw();
+}
+
+/// Publishes right away the text of the [`Content`] through the debounced and
+/// throttled closures with a pending change, so it comes before the messages
+/// of a submission or a blur.
+fn flush_delayed<
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    R: text::Renderer,
+    Message: std::fmt::Debug + Clone,
+>(
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    shell: &mut Shell<'_, Message>,
+) {
+    let now = Instant::now();
+    let edits = target.content.edits();
+    let mut text = None;
+
+    for (delayed, timer) in [
+        (target.debounced, &mut state.debounce),
+        (target.throttled, &mut state.throttle),
+    ] {
+        let Some(delayed) = delayed else {
+            continue;
+        };
+
+        if timer.flush(edits, now) {
+            let text = text.get_or_insert_with(|| target.content.text());
+
+            shell.publish((delayed.on_change)(text.clone()));
+        }
+    }
+}
+
+/// Applies the given [`Binding`] after the deferred bindings of the
+/// [`TextBox`], as soon as its [`Content`] is up to date.
+///
+/// Custom messages cannot be deferred, so they are produced right after the
+/// bindings before them are applied or deferred.
+fn enqueue<
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    R: text::Renderer,
+    Message: std::fmt::Debug + Clone,
+>(
+    binding: Binding<Message>,
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    clipboard: &mut dyn Clipboard,
+    shell: &mut Shell<'_, Message>,
+) {
+    match binding {
+        Binding::Sequence(sequence) => {
+            for binding in sequence {
+                enqueue(binding, target, state, clipboard, shell);
+            }
+        }
+        Binding::Custom(message) => {
+            resume(target, state, clipboard, shell);
+
+            shell.publish(message);
+            shell.request_redraw();
+        }
+        // The recording only depends on the order of the bindings
+        Binding::StartRecording | Binding::StopRecording => {
+            if let Some(binding) = binding.to_builtin() {
+                apply_binding(binding, target, state, clipboard, shell);
+            }
+        }
+        binding => {
+            state
+                .deferred
+                .extend(binding.to_builtin().map(Step::Binding));
+
+            resume(target, state, clipboard, shell);
+        }
+    }
+}
+
+/// A deferred step of the bindings applied to a [`TextBox`].
+#[derive(Debug, Clone)]
+enum Step {
+    /// Apply a built-in [`Binding`].
+    Binding(Binding<Infallible>),
+    /// Insert the given text in place of the selection.
+    Insert(String),
+    /// Insert the entry of the kill ring at the given index in place of the
+    /// selection.
+    Yank(usize),
+    /// Note that the entry of the kill ring at the given index was inserted
+    /// from the given position up to the caret.
+    Yanked { index: usize, start: (usize, usize) },
+    /// Replace the yanked text with the entry of the kill ring at the given
+    /// index, once the selection reaches back to the start of the text.
+    Reyank { yank: kill_ring::Yank, index: usize },
+}
+
+impl Step {
+    /// Returns true if applying the [`Step`] reads the [`Content`] of the
+    /// [`TextBox`].
+    fn reads_content(&self, is_constrained: bool) -> bool {
+        match self {
+            Self::Binding(binding) => binding.reads_content(is_constrained),
+            Self::Insert(_) => is_constrained,
+            Self::Yank(_) | Self::Yanked { .. } | Self::Reyank { .. } => true,
+        }
+    }
+}
+
+/// Applies the deferred steps of the [`TextBox`] in order, until one of
+/// them reads its [`Content`] before the actions published so far are
+/// performed.
+fn resume<
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    R: text::Renderer,
+    Message: std::fmt::Debug + Clone,
+>(
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    clipboard: &mut dyn Clipboard,
+    shell: &mut Shell<'_, Message>,
+) {
+    let is_constrained = !target.constraints.is_empty();
+
+    while let Some(step) = state.deferred.pop_front() {
+        if step.reads_content(is_constrained) && !target.content.is_settled() {
+            state.deferred.push_front(step);
+
+            // The application performs the actions before rebuilding its view
+            shell.request_redraw();
+            return;
+        }
+
+        match step {
+            Step::Binding(binding) => apply_binding(binding, target, state, clipboard, shell),
+            Step::Insert(text) => {
+                for action in target.content.insert_actions(&text, &target.constraints) {
+                    target.publisher.publish(shell, action);
+                }
+            }
+            Step::Yank(index) => yank(index, target, state, shell),
+            Step::Yanked { index, start } => {
+                state.kill_ring.yanked(kill_ring::Yank {
+                    index,
+                    start,
+                    end: target.content.cursor_position(),
+                });
+            }
+            Step::Reyank { yank, index } => reyank(yank, index, target, state, shell),
+        }
+    }
+}
+
+/// Inserts the entry of the kill ring at the given index in place of the
+/// selection of the [`TextBox`], within its [`Constraints`].
+///
+/// The selection is deleted first, so the yanked text starts at the caret.
+fn yank<Link: Clone + 'static, H: highlighter::Highlighter, R: text::Renderer, Message>(
+    index: usize,
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    shell: &mut Shell<'_, Message>,
+) {
+    let Some(entry) = state.kill_ring.entry(index).map(str::to_owned) else {
+        return;
+    };
+
+    if !state.is_focused() {
+        return;
+    }
+
+    if target.content.selection().is_some() {
+        target.publisher.publish(shell, Action::Edit(Edit::Delete));
+        prepend(&mut state.deferred, [Step::Yank(index)]);
+        return;
+    }
+
+    let start = target.content.cursor_position();
+
+    for action in target.content.insert_actions(&entry, &target.constraints) {
+        target.publisher.publish(shell, action);
+    }
+
+    prepend(&mut state.deferred, [Step::Yanked { index, start }]);
+}
+
+/// Replaces the text inserted by the [`Yank`] with the entry of the kill
+/// ring at the given index, once the selection of the [`TextBox`] extends
+/// from the caret back to the start of the yanked text.
+///
+/// The selected text before the yanked one is pasted back along with the
+/// entry.
+///
+/// [`Yank`]: kill_ring::Yank
+fn reyank<Link: Clone + 'static, H: highlighter::Highlighter, R: text::Renderer, Message>(
+    yank: kill_ring::Yank,
+    index: usize,
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    shell: &mut Shell<'_, Message>,
+) {
+    let Some(entry) = state.kill_ring.entry(index).map(str::to_owned) else {
+        return;
+    };
+
+    if !state.is_focused() {
+        return;
+    }
+
+    let from = target.content.cursor_position();
+
+    // The yanked text starts on an earlier row
+    if from > yank.start {
+        target
+            .publisher
+            .publish(shell, Action::Select(Motion::DocumentStart));
+        prepend(&mut state.deferred, [Step::Reyank { yank, index }]);
+        return;
+    }
+
+    let Some(before) = target.content.text_between(from, yank.start) else {
+        return;
+    };
+
+    for action in target
+        .content
+        .insert_actions(&(before + &entry), &target.constraints)
+    {
+        target.publisher.publish(shell, action);
+    }
+
+    prepend(
+        &mut state.deferred,
+        [Step::Yanked {
+            index,
+            start: yank.start,
+        }],
+    );
+}
+
+/// Applies a built-in [`Binding`] to the [`TextBox`], publishing the
+/// resulting [`Action`]s.
+fn apply_binding<
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    R: text::Renderer,
+    Message: std::fmt::Debug + Clone,
+>(
+    binding: Binding<Infallible>,
+    target: &Target<'_, R, Message>,
+    state: &mut State<Link, H, R::Paragraph>,
+    clipboard: &mut dyn Clipboard,
+    shell: &mut Shell<'_, Message>,
+) {
+    let Target {
+        content,
+        constraints,
+        publisher,
+        on_submit,
+        on_blur,
+        ..
+    } = target;
+
+    let binding = if state.is_secure {
+        match binding.secured() {
+            Some(binding) => binding,
+            None => return,
+        }
+    } else {
+        binding
+    };
+
+    // A single line is submitted instead of broken
+    let binding = match binding {
+        Binding::Enter if constraints.is_single_line => Binding::Submit,
+        binding => binding,
+    };
+
+    // A masked text deletes the selected word or line, so it is reformatted
+    let binding = match binding.deletion() {
+        Some((motion, delete)) if constraints.mask.is_some() => {
+            if content.selection().is_some() {
+                delete
+            } else {
+                Binding::Sequence(vec![Binding::Select(motion), delete])
+            }
+        }
+        _ => binding,
+    };
+
+    let mut publish_if_focused = |state: &mut State<Link, H, R::Paragraph>, action| {
+        if state.is_focused() {
+            publisher.publish(shell, action);
+            state.focus();
+            shell.request_redraw();
+        }
+    };
+
+    match binding {
+        Binding::Unfocus => {
+            if state.is_focused() {
+                flush_delayed(target, state, shell);
+                state.unfocus();
+                state.drag_click = None;
+                if let Some(on_blur) = on_blur {
+                    shell.publish(on_blur.clone());
+                }
+                shell.request_redraw();
+            }
+        }
+        Binding::Copy => {
+            if let Some(selection) = content.selection() {
+                clipboard.write(clipboard::Kind::Standard, selection);
+            }
+        }
+        Binding::Cut => {
+            if let Some(selection) = content.selection() {
+                clipboard.write(clipboard::Kind::Standard, selection);
+
+                for action in content.delete_actions(Edit::Delete, constraints) {
+                    publish_if_focused(state, action);
+                }
+            }
+        }
+        Binding::Paste => {
+            if let Some(contents) = clipboard.read(clipboard::Kind::Standard) {
+                for action in content.insert_actions(&contents, constraints) {
+                    publish_if_focused(state, action);
+                }
+            }
+        }
+        Binding::Move(motion) => {
+            publish_if_focused(state, Action::Move(motion));
+        }
+        Binding::Select(motion) => {
+            publish_if_focused(state, Action::Select(motion));
+        }
+        Binding::SelectWord => {
+            publish_if_focused(state, Action::SelectWord);
+        }
+        Binding::SelectLine => {
+            publish_if_focused(state, Action::SelectLine);
+        }
+        Binding::SelectLines(count) => {
+            for action in content.command_actions(Command::SelectLines(count)) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::MoveLeftInLine => {
+            for action in content.command_actions(Command::MoveLeftInLine) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::SelectAll => {
+            publish_if_focused(state, Action::SelectAll);
+        }
+        Binding::Insert(c) => {
+            for action in content.insert_actions(c.encode_utf8(&mut [0; 4]), constraints) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::Enter => {
+            for action in content.insert_actions("\n", constraints) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::Submit => {
+            if state.is_focused() && content.is_valid() {
+                flush_delayed(target, state, shell);
+                if let Some(on_submit) = on_submit {
+                    shell.publish(on_submit.clone());
                 }
+                state.unfocus();
+                shell.invalidate_layout();
+            }
+        }
+        Binding::Backspace => {
+            for action in content.delete_actions(Edit::Backspace, constraints) {
+                publish_if_focused(state, action);
+            }
+            shell.request_redraw();
+        }
+        Binding::Delete => {
+            for action in content.delete_actions(Edit::Delete, constraints) {
+                publish_if_focused(state, action);
+            }
+            shell.request_redraw();
+        }
+        Binding::DeleteWordBackward => {
+            for action in content.command_actions(Command::DeleteWordBackward) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::DeleteWordForward => {
+            for action in content.command_actions(Command::DeleteWordForward) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::DeleteToLineStart => {
+            for action in content.command_actions(Command::DeleteToLineStart) {
+                publish_if_focused(state, action);
             }
-            Event::Window(window::Event::Focused) => {
-                if let Some(focus) = &mut state.focus {
-                    focus.is_window_focused = true;
-                    focus.updated_at = Instant::now();
-
-                    shell.request_redraw();
-                    shell.invalidate_layout();
-                }
+        }
+        Binding::DeleteToLineEnd => {
+            for action in content.command_actions(Command::DeleteToLineEnd) {
+                publish_if_focused(state, action);
             }
-            Event::Window(window::Event::RedrawRequested(now)) => {
-                if let Some(focus) = &mut state.focus {
-                    if focus.is_window_focused {
-                        focus.now = now;
-
-                        let millis_until_redraw = Focus::CURSOR_BLINK_INTERVAL_MILLIS
-                            - (now - focus.updated_at).as_millis()
-                                % Focus::CURSOR_BLINK_INTERVAL_MILLIS;
-
-                        shell.request_redraw_at(window::RedrawRequest::At(
-                            now + Duration::from_millis(millis_until_redraw as u64),
-                        ));
-                    }
-                }
+        }
+        Binding::DuplicateLine => {
+            for action in content.command_actions(Command::DuplicateLine) {
+                publish_if_focused(state, action);
             }
-            _ => {}
         }
-
-        let Some(on_edit) = self.on_edit.as_ref() else {
-            return;
-        };
-
-        let Some(update) = Update::from_event::<Link, H, Renderer>(
-            event,
-            state,
-            layout.bounds(),
-            self.padding,
-            cursor,
-            self.key_binding.as_deref(),
-        ) else {
-            return;
-        };
-
-        match update {
-            Update::Click(click) => match click.kind() {
-                mouse::click::Kind::Single => {
-                    state.last_click = Some(click);
-                    state.drag_click = Some(click.kind());
-                    if state.is_focused() {
-                        shell.capture_event();
-                        shell.publish(on_edit(Action::Click(click.position())));
-                        shell.request_redraw();
-                    }
-                }
-                mouse::click::Kind::Double => {
-                    state.last_click = Some(click);
-                    state.drag_click = Some(click.kind());
-                    if state.is_focused() {
-                        shell.publish(on_edit(Action::SelectWord));
-                        shell.capture_event();
-                        shell.request_redraw();
-                    } else {
-                        state.focus();
-                        shell.invalidate_layout();
-                        shell.publish(on_edit(Action::Click(click.position())));
-                        shell.capture_event();
-                    }
+        Binding::DuplicateLineUp => {
+            for action in content.command_actions(Command::DuplicateLineUp) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::MoveLineUp => {
+            for action in content.command_actions(Command::MoveLineUp) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::MoveLineDown => {
+            for action in content.command_actions(Command::MoveLineDown) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::JoinLines => {
+            for action in content.command_actions(Command::JoinLines) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::TransposeChars => {
+            for action in content.command_actions(Command::TransposeChars) {
+                publish_if_focused(state, action);
+            }
+        }
+        Binding::KillToLineEnd => {
+            if state.is_focused()
+                && let Some((text, actions)) = content.kill_to_line_end()
+            {
+                let entry = state.kill_ring.kill(&text);
+                clipboard.write(clipboard::Kind::Standard, entry.to_owned());
+
+                for action in actions {
+                    publish_if_focused(state, action);
                 }
-                mouse::click::Kind::Triple => {
-                    if state.is_focused() {
-                        shell.publish(on_edit(Action::SelectAll));
-                        shell.capture_event();
-                        shell.request_redraw();
-                    } else {
-                        state.focus();
-                        shell.invalidate_layout();
-                        shell.publish(on_edit(Action::Click(click.position())));
-                        shell.capture_event();
-                    }
+            }
+        }
+        Binding::KillSelection => {
+            if state.is_focused()
+                && let Some(selection) = content.selection()
+            {
+                let entry = state.kill_ring.kill(&selection);
+                clipboard.write(clipboard::Kind::Standard, entry.to_owned());
+
+                for action in content.delete_actions(Edit::Delete, constraints) {
+                    publish_if_focused(state, action);
                 }
-            },
-            Update::Drag(position) => {
-                shell.capture_event();
-                shell.publish(on_edit(Action::Drag(position)));
             }
-            Update::Release => {
-                state.drag_click = None;
+        }
+        Binding::CopyToKillRing => {
+            if let Some(selection) = content.selection() {
+                let entry = state.kill_ring.kill(&selection);
+                clipboard.write(clipboard::Kind::Standard, entry.to_owned());
             }
-            Update::Scroll(lines) => {
-                let bounds = self.content.0.borrow().editor.bounds();
-
-                if bounds.height >= i32::MAX as f32 {
-                    return;
+        }
+        Binding::Yank => {
+            if state.kill_ring.entry(0).is_some() {
+                prepend(&mut state.deferred, [Step::Yank(0)]);
+            } else if let Some(contents) = clipboard.read(clipboard::Kind::Standard) {
+                for action in content.insert_actions(&contents, constraints) {
+                    publish_if_focused(state, action);
                 }
-
-                let lines = lines + state.partial_scroll;
-                state.partial_scroll = lines.fract();
-
-                shell.publish(on_edit(Action::Scroll {
-                    lines: lines as i32,
-                }));
             }
-            Update::Binding(binding) => {
-                fn apply_binding<
-                    Link: Clone + 'static,
-                    H: highlighter::Highlighter,
-                    R: text::Renderer,
-                    Message: std::fmt::Debug + Clone,
-                >(
-                    binding: Binding<Message>,
-                    content: &Content<R>,
-                    state: &mut State<Link, H, R::Paragraph>,
-                    on_edit: &dyn Fn(Action) -> Message,
-                    on_submit: &Option<Message>,
-                    on_blur: &Option<Message>,
-                    clipboard: &mut dyn Clipboard,
-                    shell: &mut Shell<'_, Message>,
-                ) {
-                    let mut publish_if_focused =
-                        |state: &mut State<Link, H, R::Paragraph>, action| {
-                            if state.is_focused() {
-                                shell.publish(on_edit(action));
-                                state.focus();
-                                shell.request_redraw();
-                            }
-                        };
-
-                    match binding {
-                        Binding::Unfocus => {
-                            if state.is_focused() {
-                                state.unfocus();
-                                state.drag_click = None;
-                                if let Some(on_blur) = on_blur {
-                                    shell.publish(on_blur.clone());
-                                }
-                                shell.request_redraw();
-                            }
-                        }
-                        Binding::Copy => {
-                            if let Some(selection) = content.selection() {
-                                clipboard.write(clipboard::Kind::Standard, selection);
-                            }
-                        }
-                        Binding::Cut => {
-                            if let Some(selection) = content.selection() {
-                                clipboard.write(clipboard::Kind::Standard, selection);
-
-                                publish_if_focused(state, Action::Edit(Edit::Delete));
-                            }
-                        }
-                        Binding::Paste => {
-                            if let Some(contents) = clipboard.read(clipboard::Kind::Standard) {
-                                publish_if_focused(
-                                    state,
-                                    Action::Edit(Edit::Paste(Arc::new(contents))),
-                                );
-                            }
-                        }
-                        Binding::Move(motion) => {
-                            publish_if_focused(state, Action::Move(motion));
-                        }
-                        Binding::Select(motion) => {
-                            publish_if_focused(state, Action::Select(motion));
-                        }
-                        Binding::SelectWord => {
-                            publish_if_focused(state, Action::SelectWord);
-                        }
-                        Binding::SelectLine => {
-                            publish_if_focused(state, Action::SelectLine);
-                        }
-                        Binding::SelectAll => {
-                            publish_if_focused(state, Action::SelectAll);
-                        }
-                        Binding::Insert(c) => {
-                            publish_if_focused(state, Action::Edit(Edit::Insert(c)));
-                        }
-                        Binding::Enter => {
-                            publish_if_focused(state, Action::Edit(Edit::Enter));
-                        }
-                        Binding::Submit => {
-                            if state.is_focused() {
-                                if let Some(on_submit) = on_submit {
-                                    shell.publish(on_submit.clone());
-                                }
-                                state.unfocus();
-                                shell.invalidate_layout();
-                            }
-                        }
-                        Binding::Backspace => {
-                            publish_if_focused(state, Action::Edit(Edit::Backspace));
-                            shell.request_redraw();
-                        }
-                        Binding::Delete => {
-                            publish_if_focused(state, Action::Edit(Edit::Delete));
-                            shell.request_redraw();
-                        }
-                        Binding::Sequence(sequence) => {
-                            for binding in sequence {
-                                apply_binding(
-                                    binding, content, state, on_edit, on_submit, on_blur,
-                                    clipboard, shell,
-                                );
-                            }
-                        }
-                        Binding::Custom(message) => {
-                            shell.publish(message);
-                            shell.request_redraw();
-                        }
-                    }
-                }
-
-                apply_binding(
-                    binding,
-                    self.content,
-                    state,
-                    on_edit,
-                    &self.on_submit,
-                    &self.on_blur,
-                    clipboard,
-                    shell,
+        }
+        // The yanked text is selected, usually along with the start of its
+        // row, and replaced with the previous entry, unless the caret moved
+        // away from it
+        Binding::YankPop => {
+            if let Some((yank, previous)) = state.kill_ring.yank_pop()
+                && content.is_after(&yank)
+            {
+                publish_if_focused(state, Action::Select(Motion::Home));
+                prepend(
+                    &mut state.deferred,
+                    [Step::Reyank {
+                        yank,
+                        index: previous,
+                    }],
                 );
-
-                if let Some(focus) = &mut state.focus {
-                    focus.updated_at = Instant::now();
-                }
             }
         }
-    }
-
-    fn mouse_interaction(
-        &self,
-        state: &Tree,
-        layout: Layout<'_>,
-        cursor: mouse::Cursor,
-        _viewport: &Rectangle,
-        _renderer: &Renderer,
-    ) -> mouse::Interaction {
-        let state = state
-            .state
-            .downcast_ref::<State<Link, H, Renderer::Paragraph>>();
-
-        if cursor.is_over(layout.bounds()) && state.is_focused() {
-            mouse::Interaction::Text
-        } else {
-            mouse::Interaction::default()
+        Binding::StartRecording => {
+            if state.is_focused() {
+                state.recorder.start();
+            }
+        }
+        Binding::StopRecording => {
+            state.recorder.stop();
+        }
+        // Each binding of the sequence waits for the ones before it
+        Binding::Replay(count) => {
+            let recorded = state.recorder.recorded();
+
+            prepend(
+                &mut state.deferred,
+                std::iter::repeat_n(recorded, count)
+                    .flatten()
+                    .map(Step::Binding),
+            );
+        }
+        Binding::Sequence(sequence) => {
+            prepend(&mut state.deferred, sequence.into_iter().map(Step::Binding));
         }
+        Binding::Custom(never) => match never {},
     }
+}
 
-    fn operate(
-        &self,
-        tree: &mut Tree,
-        layout: Layout<'_>,
-        _renderer: &Renderer,
-        operation: &mut dyn Operation,
-    ) {
-        let state = tree
-            .state
-            .downcast_mut::<State<Link, H, Renderer::Paragraph>>();
-
-        operation.focusable(self.id.as_ref(), layout.bounds(), state);
+/// Puts the given steps before the deferred ones, in order.
+fn prepend(deferred: &mut VecDeque<Step>, steps: impl IntoIterator<Item = Step>) {
+    for step in steps.into_iter().collect::<Vec<_>>().into_iter().rev() {
+        deferred.push_front(step);
     }
 }
 
@@ -1187,19 +3784,68 @@ pub fn draw_text<Renderer>(
     );
 }
 
+/// Draws the pre-edit text of an input method composition on the spot, at the
+/// given caret position, underlining it and highlighting its selected segment.
+fn draw_preedit<Renderer>(
+    renderer: &mut Renderer,
+    preedit: &input_method::Preedit,
+    paragraph: &Renderer::Paragraph,
+    position: Point,
+    background: Background,
+    color: Color,
+    clip_bounds: Rectangle,
+) where
+    Renderer: text::Renderer,
+{
+    let size = paragraph.min_bounds();
+    let bounds = Rectangle::new(position, size);
+
+    // Hide whatever is behind the composition
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            ..renderer::Quad::default()
+        },
+        background,
+    );
+
+    renderer.fill_paragraph(paragraph, position, color, clip_bounds);
+
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds: Rectangle::new(
+                position + Vector::new(0.0, size.height - 1.0),
+                Size::new(size.width, 1.0),
+            ),
+            ..renderer::Quad::default()
+        },
+        color,
+    );
+
+    // Emphasize the segment being converted with a thicker underline
+    if let Some(selection) = preedit.selection.as_ref().filter(|s| !s.is_empty()) {
+        let start = preedit_x(paragraph, &preedit.content, selection.start);
+        let end = preedit_x(paragraph, &preedit.content, selection.end);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle::new(
+                    position + Vector::new(start, size.height - 2.0),
+                    Size::new(end - start, 2.0),
+                ),
+                ..renderer::Quad::default()
+            },
+            color,
+        );
+    }
+}
+
 fn layout_spans<Link, H, Renderer>(
     state: &mut State<Link, H, Renderer::Paragraph>,
-    _renderer: &Renderer,
     limits: &layout::Limits,
     width: Length,
     height: Length,
-    spans: &[Span<'_, Link, Renderer::Font>],
-    line_height: LineHeight,
-    size: Pixels,
-    font: Renderer::Font,
-    align_x: text::Alignment,
-    align_y: alignment::Vertical,
-    wrapping: Wrapping,
+    text: Text<&[Span<'_, Link, Renderer::Font>], Renderer::Font>,
 ) -> layout::Node
 where
     Link: Clone,
@@ -1209,32 +3855,22 @@ where
     layout::sized(limits, width, height, |limits| {
         let bounds = limits.max();
 
-        let text_with_spans = || Text {
-            content: spans,
-            bounds,
-            size,
-            line_height,
-            font,
-            align_x,
-            align_y,
-            shaping: Shaping::Advanced,
-            wrapping,
-        };
+        let text_with_spans = || Text { bounds, ..text };
 
-        if state.spans != spans {
+        if state.spans != text.content {
             state.paragraph = Renderer::Paragraph::with_spans(text_with_spans());
-            state.spans = spans.iter().cloned().map(Span::to_static).collect();
+            state.spans = text.content.iter().cloned().map(Span::to_static).collect();
         } else {
             match state.paragraph.compare(Text {
                 content: (),
                 bounds,
-                size,
-                line_height,
-                font,
-                align_x,
-                align_y,
-                shaping: Shaping::Advanced,
-                wrapping,
+                size: text.size,
+                line_height: text.line_height,
+                font: text.font,
+                align_x: text.align_x,
+                align_y: text.align_y,
+                shaping: text.shaping,
+                wrapping: text.wrapping,
             }) {
                 Difference::None => {}
                 Difference::Bounds => {
@@ -1266,6 +3902,65 @@ where
     }
 }
 
+/// The shape of the caret of a [`TextBox`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaretShape {
+    /// A thin vertical bar before the next character.
+    #[default]
+    Bar,
+    /// A block covering the next character.
+    Block,
+    /// A line under the next character.
+    Underline,
+}
+
+/// The configuration of the caret of a [`TextBox`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaretStyle {
+    /// The [`CaretShape`] of the caret, unless the keymap overrides it.
+    pub shape: CaretShape,
+    /// The width of a bar caret, or the thickness of an underline caret, in
+    /// pixels.
+    pub width: f32,
+    /// The time the caret stays visible, and then hidden, while blinking; or
+    /// `None` to never blink.
+    pub blink: Option<Duration>,
+}
+
+impl Default for CaretStyle {
+    fn default() -> Self {
+        Self {
+            shape: CaretShape::Bar,
+            width: 1.0,
+            blink: Some(Duration::from_millis(500)),
+        }
+    }
+}
+
+/// An icon shown next to the text of a [`TextBox`].
+#[derive(Debug, Clone)]
+pub struct Icon<Font> {
+    /// The font of the icon.
+    pub font: Font,
+    /// The unicode code point of the icon.
+    pub code_point: char,
+    /// The font size of the icon, or the text size of the [`TextBox`] if `None`.
+    pub size: Option<Pixels>,
+    /// The space between the icon and the text.
+    pub spacing: f32,
+    /// The [`Side`] of the [`TextBox`] where the icon is shown.
+    pub side: Side,
+}
+
+/// The side of a [`TextBox`] where its [`Icon`] is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Before the text.
+    Left,
+    /// After the text.
+    Right,
+}
+
 /// The possible status of a [`TextBox`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Status {
@@ -1277,6 +3972,8 @@ pub enum Status {
     Focused,
     /// The [`TextBox`] cannot be interacted with.
     Disabled,
+    /// The text of the [`TextBox`] failed its validation.
+    Invalid,
 }
 
 /// The appearance of a textbox.
@@ -1294,6 +3991,42 @@ pub struct Style {
     pub value: Option<Color>,
     /// The [`Color`] of the selection of the textbox.
     pub selection: Color,
+    /// The [`Color`] of the caret of the textbox.
+    pub caret: Color,
+    /// The appearance of the scrollbar of a scrollable textbox.
+    pub scrollbar: ScrollbarStyle,
+    /// The [`Shadow`] of the textbox.
+    pub shadow: Shadow,
+    /// The appearance of the ring drawn around the textbox while editing.
+    pub focus_ring: FocusRingStyle,
+    /// The [`Color`] of the message of a failed validation of the textbox.
+    pub error: Color,
+    /// The [`Color`] of the character counter of the textbox.
+    pub counter: Color,
+}
+
+/// The appearance of the ring drawn around a [`TextBox`] while editing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FocusRingStyle {
+    /// The width of the ring; only its [`Shadow`] is drawn if zero.
+    pub width: f32,
+    /// The gap between the border of the textbox and the ring.
+    pub offset: f32,
+    /// The [`Color`] of the ring.
+    pub color: Color,
+    /// The [`Shadow`] cast by the ring.
+    pub shadow: Shadow,
+}
+
+/// The appearance of the scrollbar of a scrollable textbox.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollbarStyle {
+    /// The [`Background`] of the rail of the scrollbar.
+    pub background: Option<Background>,
+    /// The [`Border`] of the rail of the scrollbar.
+    pub border: Border,
+    /// The [`Color`] of the scroller of the scrollbar.
+    pub scroller: Color,
 }
 
 /// The theme catalog of a [`TextBox`].
@@ -1338,6 +4071,20 @@ pub fn default(theme: &iced::Theme, status: Status) -> Style {
         placeholder: palette.background.strong.color,
         value: Some(palette.background.base.text),
         selection: palette.primary.weak.color,
+        caret: palette.background.base.text,
+        scrollbar: ScrollbarStyle {
+            background: None,
+            border: Border {
+                radius: 3.0.into(),
+                width: 0.0,
+                color: Color::TRANSPARENT,
+            },
+            scroller: palette.background.strong.color,
+        },
+        shadow: Shadow::default(),
+        focus_ring: FocusRingStyle::default(),
+        error: palette.danger.base.color,
+        counter: palette.background.strong.color,
     };
 
     match status {
@@ -1354,6 +4101,12 @@ pub fn default(theme: &iced::Theme, status: Status) -> Style {
                 color: palette.primary.strong.color,
                 ..active.border
             },
+            focus_ring: FocusRingStyle {
+                width: 2.0,
+                offset: 1.0,
+                color: palette.primary.weak.color,
+                shadow: Shadow::default(),
+            },
             ..active
         },
         Status::Disabled => Style {
@@ -1361,6 +4114,19 @@ pub fn default(theme: &iced::Theme, status: Status) -> Style {
             value: Some(active.placeholder),
             ..active
         },
+        Status::Invalid => Style {
+            border: Border {
+                color: palette.danger.base.color,
+                ..active.border
+            },
+            focus_ring: FocusRingStyle {
+                width: 2.0,
+                offset: 1.0,
+                color: palette.danger.weak.color,
+                shadow: Shadow::default(),
+            },
+            ..active
+        },
     }
 }
 
@@ -1393,3 +4159,96 @@ where
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_every_edit() {
+        let mut content = Content::<iced::Renderer>::with_text("12");
+
+        content.set_validator(Rc::new(|text: &str| {
+            if text.chars().all(|c| c.is_ascii_digit()) {
+                Ok(())
+            } else {
+                Err(String::from("Only digits"))
+            }
+        }));
+
+        assert!(content.is_valid());
+
+        content.perform(Action::Move(Motion::DocumentEnd));
+        content.perform(Action::Edit(Edit::Insert('a')));
+
+        assert_eq!(content.error().as_deref(), Some("Only digits"));
+
+        content.perform(Action::Edit(Edit::Backspace));
+
+        assert!(content.is_valid());
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Recorded(Vec<Binding<Message>>),
+    }
+
+    #[test]
+    fn records_and_replays_bindings() {
+        let content = Rc::new(Content::<iced::Renderer>::with_text("ab"));
+        let text_box: TextBox<'_, (), _, Message, iced::Theme, iced::Renderer> =
+            TextBox::shared([], Rc::clone(&content)).on_record(Message::Recorded);
+
+        let mut tree_state = Widget::state(&text_box);
+        let state = tree_state.downcast_mut::<State<
+            (),
+            highlighter::PlainText,
+            <iced::Renderer as text::Renderer>::Paragraph,
+        >>();
+        state.focus = Some(Focus::now());
+
+        let target = text_box.target();
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        let mut clipboard = clipboard::Null;
+
+        let mut handle = |binding, state: &mut State<_, _, _>| {
+            handle_binding(binding, &target, state, &mut clipboard, &mut shell);
+        };
+
+        let recorded = vec![Binding::Move(Motion::DocumentEnd), Binding::Insert('c')];
+
+        handle(Binding::StartRecording, state);
+        handle(Binding::Move(Motion::DocumentEnd), state);
+        handle(Binding::Insert('c'), state);
+        handle(Binding::StopRecording, state);
+
+        assert_eq!(content.text(), "abc");
+        assert_eq!(state.recorder.recorded::<Message>(), recorded);
+
+        handle(Binding::Replay(2), state);
+
+        assert_eq!(content.text(), "abccc");
+        assert_eq!(state.recorder.recorded::<Message>(), recorded);
+
+        // A replay in another recording is not recorded again
+        handle(Binding::StartRecording, state);
+        handle(Binding::Replay(1), state);
+        handle(Binding::Backspace, state);
+        handle(Binding::StopRecording, state);
+
+        assert_eq!(content.text(), "abccc");
+        assert_eq!(
+            state.recorder.recorded::<Message>(),
+            vec![Binding::Backspace]
+        );
+
+        assert_eq!(
+            messages,
+            vec![
+                Message::Recorded(recorded),
+                Message::Recorded(vec![Binding::Backspace])
+            ]
+        );
+    }
+}
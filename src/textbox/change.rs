@@ -0,0 +1,354 @@
+//! Describe the edits applied to a [`Content`] as structured diffs.
+use std::ops::Range;
+
+use super::{Content, Line, LineEnding};
+use crate::core::text;
+
+/// An edit applied to a [`Content`], as the text it replaced and the text
+/// it inserted in its place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The range of bytes of the replaced text, in the text before the
+    /// [`Change`].
+    pub range: Range<usize>,
+    /// The line and byte column of the start of the replaced text.
+    pub start: (usize, usize),
+    /// The line and byte column of the end of the replaced text, in the text
+    /// before the [`Change`].
+    pub end: (usize, usize),
+    /// The text inserted in place of the replaced text.
+    pub text: String,
+    /// The version of the [`Content`] after the [`Change`].
+    pub version: u64,
+}
+
+/// The lines an [`Edit`] is about to change, whose [`Change`] is read once
+/// it is performed.
+///
+/// [`Edit`]: super::Edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Pending {
+    /// The index of the first line that may change.
+    first: usize,
+    /// The amount of lines after the last one that may change.
+    rest: usize,
+    /// The byte offset of the first line that may change.
+    offset: usize,
+    /// The byte offset of the edit in the lines, where a [`Change`] starts
+    /// unless it is found before.
+    start: usize,
+    /// The length in bytes of each line that may change, with its line break.
+    lengths: Vec<usize>,
+    /// The text of the lines that may change, with the line breaks between
+    /// them.
+    text: String,
+}
+
+impl Pending {
+    /// Returns the [`Pending`] change of an [`Edit`], given the line and
+    /// byte column of the caret, the ones of the bounds of the selection, if
+    /// any, and the amount of lines and the lines of the text before the
+    /// [`Edit`].
+    ///
+    /// An [`Edit`] only changes the lines of the selection or the caret, and
+    /// the ones right around them when joining lines.
+    ///
+    /// [`Edit`]: super::Edit
+    pub(super) fn new<'a>(
+        caret: (usize, usize),
+        selection: Option<((usize, usize), (usize, usize))>,
+        line_count: usize,
+        line: impl Fn(usize) -> Option<Line<'a>>,
+    ) -> Self {
+        let (start, end) = selection.unwrap_or((caret, caret));
+
+        let last = line_count.saturating_sub(1);
+        let first = start.0.min(caret.0).saturating_sub(1).min(last);
+        let end = (end.0.max(caret.0) + 1).min(last);
+
+        let lengths = (first..=end)
+            .map_while(&line)
+            .map(|line| line.text.len() + separator(line.ending).len())
+            .collect();
+
+        let offset = offset((first, 0), &line);
+
+        Self {
+            first,
+            rest: last - end,
+            offset,
+            start: self::offset(start.min(caret), &line) - offset,
+            lengths,
+            text: lines(first..=end, &line),
+        }
+    }
+
+    /// Returns the [`Change`] made by the [`Edit`], given the version of the
+    /// [`Content`] and the amount of lines and the lines of its text after
+    /// the [`Edit`].
+    ///
+    /// The [`Change`] is the part of the lines that differs, if any.
+    ///
+    /// [`Edit`]: super::Edit
+    pub(super) fn finish<'a>(
+        self,
+        version: u64,
+        line_count: usize,
+        line: impl Fn(usize) -> Option<Line<'a>>,
+    ) -> Option<Change> {
+        let last = line_count.checked_sub(self.rest + 1)?.max(self.first);
+        let after = lines(self.first..=last, &line);
+
+        let before = self.text.as_str();
+
+        if before == after {
+            return None;
+        }
+
+        // Repeated characters may differ anywhere, so the edit comes first
+        let start = common_prefix(before, &after).min(self.start);
+        let suffix = common_suffix(&before[start..], &after[start..]);
+        let end = before.len() - suffix;
+
+        Some(Change {
+            range: self.offset + start..self.offset + end,
+            start: self.position(start),
+            end: self.position(end),
+            text: after[start..after.len() - suffix].to_owned(),
+            version,
+        })
+    }
+
+    /// Returns the line and byte column of the given byte offset in the
+    /// lines that may change.
+    fn position(&self, mut offset: usize) -> (usize, usize) {
+        let mut index = self.first;
+
+        for length in &self.lengths[..self.lengths.len().saturating_sub(1)] {
+            if offset < *length {
+                break;
+            }
+
+            offset -= length;
+            index += 1;
+        }
+
+        (index, offset)
+    }
+}
+
+/// Returns the length in bytes of the longest common start of the texts.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(a.len().min(b.len()), |((index, _), _)| index)
+}
+
+/// Returns the length in bytes of the longest common end of the texts.
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum()
+}
+
+/// Returns the byte offset of the given line and byte column in the text.
+fn offset<'a>((index, column): (usize, usize), line: impl Fn(usize) -> Option<Line<'a>>) -> usize {
+    (0..index)
+        .filter_map(&line)
+        .map(|line| line.text.len() + separator(line.ending).len())
+        .sum::<usize>()
+        + column
+}
+
+/// Returns the text of the given lines, with the line breaks between them.
+fn lines<'a>(
+    range: std::ops::RangeInclusive<usize>,
+    line: impl Fn(usize) -> Option<Line<'a>>,
+) -> String {
+    let last = *range.end();
+    let mut text = String::new();
+
+    for index in range {
+        let Some(line) = line(index) else {
+            break;
+        };
+
+        text.push_str(&line.text);
+
+        if index < last {
+            text.push_str(separator(line.ending));
+        }
+    }
+
+    text
+}
+
+/// Returns the line break after a line that is followed by another one.
+fn separator(ending: LineEnding) -> &'static str {
+    if ending == LineEnding::None {
+        LineEnding::default().as_str()
+    } else {
+        ending.as_str()
+    }
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Returns the version of the [`Content`], which is increased by every
+    /// edit performed on it.
+    pub fn version(&self) -> u64 {
+        self.0.borrow().version
+    }
+
+    /// Starts keeping the [`Change`] of every edit performed on the
+    /// [`Content`], until they are taken.
+    pub(super) fn track_changes(&self) {
+        self.0.borrow_mut().changes.get_or_insert_with(Vec::new);
+    }
+
+    /// Takes the [`Change`] of every edit performed on the [`Content`] since
+    /// the last time they were taken.
+    pub(super) fn take_changes(&self) -> Vec<Change> {
+        self.0
+            .borrow_mut()
+            .changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::textbox::{Action, Counts, Edit, Motion};
+
+    fn line<'a>(lines: &'a [&'a str]) -> impl Fn(usize) -> Option<Line<'a>> {
+        |index| {
+            lines.get(index).map(|text| Line {
+                text: Cow::Borrowed(*text),
+                ending: if index + 1 < lines.len() {
+                    LineEnding::Lf
+                } else {
+                    LineEnding::None
+                },
+            })
+        }
+    }
+
+    fn change(
+        before: &[&str],
+        after: &[&str],
+        caret: (usize, usize),
+        selection: Option<((usize, usize), (usize, usize))>,
+    ) -> Option<Change> {
+        Pending::new(caret, selection, before.len(), line(before)).finish(
+            1,
+            after.len(),
+            line(after),
+        )
+    }
+
+    #[test]
+    fn insert_at_caret() {
+        let change = change(&["hello", "world"], &["hello", "world!"], (1, 5), None);
+
+        assert_eq!(
+            change,
+            Some(Change {
+                range: 11..11,
+                start: (1, 5),
+                end: (1, 5),
+                text: String::from("!"),
+                version: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn paste_over_selection() {
+        let change = change(
+            &["hello", "world"],
+            &["hellXY", "Zorld"],
+            (1, 1),
+            Some(((0, 4), (1, 1))),
+        )
+        .unwrap();
+
+        assert_eq!(change.range, 4..7);
+        assert_eq!((change.start, change.end), ((0, 4), (1, 1)));
+        assert_eq!(change.text, "XY\nZ");
+    }
+
+    #[test]
+    fn backspace_joins_lines() {
+        let change = change(&["ab", "cd"], &["abcd"], (1, 0), None).unwrap();
+
+        assert_eq!(change.range, 2..3);
+        assert_eq!((change.start, change.end), ((0, 2), (1, 0)));
+        assert!(change.text.is_empty());
+    }
+
+    #[test]
+    fn repeated_characters_change_at_the_caret() {
+        let change = change(&["aa"], &["aaa"], (0, 0), None).unwrap();
+
+        assert_eq!(change.range, 0..0);
+        assert_eq!(change.text, "a");
+    }
+
+    #[test]
+    fn nothing_changed() {
+        assert_eq!(change(&["ab"], &["ab"], (0, 0), None), None);
+    }
+
+    #[test]
+    fn rewritten_lines() {
+        let change = change(
+            &["a", "b", "c"],
+            &["a", "    b", "    c"],
+            (2, 1),
+            Some(((1, 0), (2, 1))),
+        )
+        .unwrap();
+
+        assert_eq!(change.range, 2..4);
+        assert_eq!((change.start, change.end), ((1, 0), (2, 0)));
+        assert_eq!(change.text, "    b\n    ");
+    }
+
+    #[test]
+    fn follows_the_editor() {
+        let mut content = Content::<iced::Renderer>::with_text("e\u{301}x\nab");
+        content.track_changes();
+
+        let _ = content.stats();
+
+        content.perform(Action::Move(Motion::Right));
+        content.perform(Action::Edit(Edit::Backspace));
+        content.perform(Action::Edit(Edit::Delete));
+        content.perform(Action::Edit(Edit::Insert('\u{1b}')));
+        content.perform(Action::Edit(Edit::Paste(Arc::new(String::from("é\n")))));
+
+        let changes = content.take_changes();
+
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.range.clone(), change.text.as_str()))
+                .collect::<Vec<_>>(),
+            [(1..3, ""), (1..2, ""), (1..1, "é\n")]
+        );
+
+        assert_eq!(content.stats().text, Counts::of(&content.text()));
+    }
+}
@@ -0,0 +1,235 @@
+//! Resolve key bindings made of several key presses, like `ctrl+k ctrl+c`.
+use std::time::{Duration, Instant};
+
+use super::keymap::{Chord, Shortcut};
+use super::{Binding, KeyPress};
+use crate::core::keyboard::{self, key};
+
+/// The time to wait for the next key press of a pending chord.
+pub(super) const TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// The key presses of a chord that was started, but not completed yet.
+#[derive(Debug, Clone)]
+pub(super) struct Pending {
+    key_presses: Vec<KeyPress>,
+    pub(super) started_at: Instant,
+}
+
+impl Pending {
+    /// Returns the label of the indicator of the pending chord.
+    pub(super) fn label(&self) -> String {
+        let mut label = self
+            .key_presses
+            .iter()
+            .filter_map(Shortcut::from_key_press)
+            .map(|shortcut| shortcut.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        label.push_str(" …");
+        label
+    }
+
+    /// Returns the key presses of the [`Pending`] chord if it timed out.
+    pub(super) fn expire(pending: &mut Option<Self>, now: Instant) -> Option<Vec<KeyPress>> {
+        if pending
+            .as_ref()
+            .is_some_and(|pending| now >= pending.started_at + TIMEOUT)
+        {
+            pending.take().map(|pending| pending.key_presses)
+        } else {
+            None
+        }
+    }
+}
+
+/// The outcome of resolving a key press against some chords.
+pub(super) enum Resolution<Message> {
+    /// The key press is not part of any chord.
+    Unmatched,
+    /// The key press starts or continues some chord, which is not complete.
+    Pending,
+    /// The key presses complete a chord.
+    Matched(Binding<Message>),
+    /// The key presses started some chord, but the last one broke it; they
+    /// must be handled as normal input, and the last one resolved again, as
+    /// it may start some other chord.
+    Replay(Vec<KeyPress>, Box<Self>),
+}
+
+/// Resolves the given [`KeyPress`] against the chords, buffering it if it
+/// only starts or continues some of them.
+///
+/// A chord completed by the key presses takes precedence over any longer
+/// chord starting with them.
+pub(super) fn resolve<Message: Clone>(
+    chords: &[(Chord, Binding<Message>)],
+    pending: &mut Option<Pending>,
+    key_press: KeyPress,
+    now: Instant,
+) -> Resolution<Message> {
+    // Modifiers are pressed on their own in between the keys of a chord
+    if let keyboard::Key::Named(
+        key::Named::Control | key::Named::Shift | key::Named::Alt | key::Named::Super,
+    ) = key_press.key
+    {
+        return if pending.is_some() {
+            Resolution::Pending
+        } else {
+            Resolution::Unmatched
+        };
+    }
+
+    let (mut key_presses, started_at) = pending.take().map_or((Vec::new(), now), |pending| {
+        (pending.key_presses, pending.started_at)
+    });
+
+    key_presses.push(key_press);
+
+    let mut is_started = false;
+
+    for (chord, binding) in chords {
+        match chord.matches(&key_presses) {
+            Some(true) => return Resolution::Matched(binding.clone()),
+            Some(false) => is_started = true,
+            None => {}
+        }
+    }
+
+    if is_started {
+        *pending = Some(Pending {
+            key_presses,
+            started_at,
+        });
+
+        Resolution::Pending
+    } else if key_presses.len() > 1
+        && let Some(last) = key_presses.pop()
+    {
+        let resolution = resolve(chords, pending, last, now);
+
+        Resolution::Replay(key_presses, Box::new(resolution))
+    } else {
+        Resolution::Unmatched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textbox::Status;
+
+    fn control(c: char) -> KeyPress {
+        KeyPress {
+            key: keyboard::Key::Character(c.to_string().into()),
+            modifiers: keyboard::Modifiers::CTRL,
+            text: None,
+            status: Status::Focused,
+        }
+    }
+
+    fn chords() -> Vec<(Chord, Binding<()>)> {
+        vec![
+            ("ctrl+k ctrl+c".parse().unwrap(), Binding::Copy),
+            ("ctrl+k ctrl+x".parse().unwrap(), Binding::Cut),
+            ("ctrl+j".parse().unwrap(), Binding::SelectAll),
+        ]
+    }
+
+    #[test]
+    fn prefixes_are_pending() {
+        let chords = chords();
+        let mut pending = None;
+        let now = Instant::now();
+
+        assert!(matches!(
+            resolve(&chords, &mut pending, control('k'), now),
+            Resolution::Pending
+        ));
+        assert_eq!(pending.as_ref().unwrap().label(), "ctrl+k …");
+
+        // Modifiers pressed on their own keep the chord pending
+        let modifier = KeyPress {
+            key: keyboard::Key::Named(key::Named::Control),
+            ..control('k')
+        };
+
+        assert!(matches!(
+            resolve(&chords, &mut pending, modifier, now),
+            Resolution::Pending
+        ));
+        assert!(pending.is_some());
+    }
+
+    #[test]
+    fn completions_match() {
+        let chords = chords();
+        let mut pending = None;
+        let now = Instant::now();
+
+        let _ = resolve(&chords, &mut pending, control('k'), now);
+
+        assert!(matches!(
+            resolve(&chords, &mut pending, control('x'), now),
+            Resolution::Matched(Binding::Cut)
+        ));
+        assert!(pending.is_none());
+
+        assert!(matches!(
+            resolve(&chords, &mut pending, control('j'), now),
+            Resolution::Matched(Binding::SelectAll)
+        ));
+        assert!(pending.is_none());
+    }
+
+    #[test]
+    fn pending_chords_expire() {
+        let chords = chords();
+        let mut pending = None;
+        let now = Instant::now();
+
+        let _ = resolve(&chords, &mut pending, control('k'), now);
+
+        assert!(Pending::expire(&mut pending, now + TIMEOUT / 2).is_none());
+        assert!(pending.is_some());
+
+        let key_presses = Pending::expire(&mut pending, now + TIMEOUT).unwrap();
+
+        assert_eq!(key_presses.len(), 1);
+        assert!(pending.is_none());
+    }
+
+    #[test]
+    fn other_keys_break_the_chord() {
+        let chords = chords();
+        let mut pending = None;
+        let now = Instant::now();
+
+        assert!(matches!(
+            resolve(&chords, &mut pending, control('a'), now),
+            Resolution::Unmatched
+        ));
+
+        let _ = resolve(&chords, &mut pending, control('k'), now);
+
+        match resolve(&chords, &mut pending, control('a'), now) {
+            Resolution::Replay(key_presses, last) => {
+                assert_eq!(key_presses.len(), 1);
+                assert!(matches!(*last, Resolution::Unmatched));
+            }
+            _ => panic!("the chord is not broken"),
+        }
+        assert!(pending.is_none());
+
+        // The key press that broke the chord may start another one
+        let _ = resolve(&chords, &mut pending, control('k'), now);
+
+        match resolve(&chords, &mut pending, control('j'), now) {
+            Resolution::Replay(key_presses, last) => {
+                assert_eq!(key_presses.len(), 1);
+                assert!(matches!(*last, Resolution::Matched(Binding::SelectAll)));
+            }
+            _ => panic!("the chord is not broken"),
+        }
+    }
+}
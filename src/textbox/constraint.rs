@@ -0,0 +1,532 @@
+//! Constrain the text inserted in a text box, like limiting its length.
+use std::sync::Arc;
+
+use super::{Action, Content, Edit, Motion};
+use crate::core::text;
+
+/// The constraints on the text inserted in a [`TextBox`].
+///
+/// [`TextBox`]: super::TextBox
+#[derive(Clone, Copy, Default)]
+pub(super) struct Constraints<'a> {
+    pub(super) max_chars: Option<usize>,
+    pub(super) max_lines: Option<usize>,
+    pub(super) filter: Option<&'a dyn Fn(char) -> bool>,
+    pub(super) mask: Option<&'a InputMask>,
+    pub(super) is_single_line: bool,
+}
+
+impl Constraints<'_> {
+    /// Returns true if there are no constraints at all.
+    pub(super) fn is_empty(&self) -> bool {
+        self.max_chars.is_none()
+            && self.max_lines.is_none()
+            && self.filter.is_none()
+            && self.mask.is_none()
+            && !self.is_single_line
+    }
+
+    fn is_allowed(&self, c: char) -> bool {
+        self.filter.is_none_or(|filter| filter(c))
+    }
+}
+
+/// An input mask of a [`TextBox`], like `(999) 999-9999` for phone numbers or
+/// `9999-99-99` for dates.
+///
+/// In a mask, `9` stands for a digit, `a` for a letter and `*` for a letter
+/// or a digit. Any other character is a literal, which is inserted as soon as
+/// the input reaches it; a `\` makes the next character a literal.
+///
+/// [`TextBox`]: super::TextBox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputMask {
+    slots: Vec<Slot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Digit,
+    Letter,
+    Alphanumeric,
+    Literal(char),
+}
+
+impl Slot {
+    fn fits(self, c: char) -> bool {
+        match self {
+            Self::Digit => c.is_ascii_digit(),
+            Self::Letter => c.is_alphabetic(),
+            Self::Alphanumeric => c.is_alphanumeric(),
+            Self::Literal(literal) => c == literal,
+        }
+    }
+}
+
+impl InputMask {
+    /// Creates a new [`InputMask`] from its pattern.
+    pub fn new(pattern: &str) -> Self {
+        let mut chars = pattern.chars();
+        let mut slots = Vec::new();
+
+        while let Some(c) = chars.next() {
+            slots.push(match c {
+                '9' => Slot::Digit,
+                'a' => Slot::Letter,
+                '*' => Slot::Alphanumeric,
+                '\\' => match chars.next() {
+                    Some(c) => Slot::Literal(c),
+                    None => break,
+                },
+                c => Slot::Literal(c),
+            });
+        }
+
+        Self { slots }
+    }
+
+    /// Returns the rest of the [`InputMask`] after the given amount of
+    /// characters, showing every slot as `_`.
+    pub fn placeholder(&self, from: usize) -> String {
+        self.slots
+            .iter()
+            .skip(from)
+            .map(|slot| match slot {
+                Slot::Literal(c) => *c,
+                _ => '_',
+            })
+            .collect()
+    }
+
+    /// Formats the given input, which starts with a prefix of the given
+    /// amount of characters, with the [`InputMask`].
+    ///
+    /// Returns the formatted text and the amount of its characters before
+    /// the caret, which is placed right after the prefix and any literals
+    /// following it.
+    fn format(&self, input: &[char], prefix: usize) -> (String, usize) {
+        let mut output = String::new();
+        let mut length = 0;
+        let mut caret = None;
+        let mut next = 0;
+
+        for slot in &self.slots {
+            match *slot {
+                Slot::Literal(literal) => {
+                    // Literals are only inserted once there is some input
+                    if input.is_empty() {
+                        break;
+                    }
+
+                    if input.get(next) == Some(&literal) {
+                        next += 1;
+                    }
+
+                    output.push(literal);
+                }
+                slot => {
+                    // Input that does not fit the slot is dropped
+                    while input.get(next).is_some_and(|c| !slot.fits(*c)) {
+                        next += 1;
+                    }
+
+                    if caret.is_none() && next >= prefix {
+                        caret = Some(length);
+                    }
+
+                    let Some(c) = input.get(next) else {
+                        break;
+                    };
+
+                    next += 1;
+                    output.push(*c);
+                }
+            }
+
+            length += 1;
+        }
+
+        (output, caret.unwrap_or(length))
+    }
+}
+
+impl From<&str> for InputMask {
+    fn from(pattern: &str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Returns the given text without the characters rejected by the
+    /// [`Constraints`], truncated to fit in the [`Content`] in place of its
+    /// selection.
+    fn constrain(&self, text: &str, constraints: &Constraints<'_>) -> String {
+        let selection = self.selection().unwrap_or_default();
+
+        let count = |text: &str| text.chars().filter(|c| !matches!(c, '\n' | '\r')).count();
+        let breaks = |text: &str| text.matches('\n').count();
+
+        let chars = self
+            .lines()
+            .map(|line| line.text.chars().count())
+            .sum::<usize>();
+
+        let mut available_chars = constraints
+            .max_chars
+            .map(|max| max.saturating_sub(chars.saturating_sub(count(&selection))));
+
+        let mut available_breaks = constraints
+            .max_lines
+            .map(|max| max.saturating_sub(self.line_count().saturating_sub(breaks(&selection))));
+
+        let mut constrained = String::new();
+
+        for c in text.chars() {
+            match c {
+                '\r' => {}
+                // A single line takes line breaks as spaces
+                '\n' if constraints.is_single_line => match &mut available_chars {
+                    Some(0) => break,
+                    Some(available) => {
+                        *available -= 1;
+                        constrained.push(' ');
+                    }
+                    None => constrained.push(' '),
+                },
+                '\n' => match &mut available_breaks {
+                    Some(0) => break,
+                    Some(available) => {
+                        *available -= 1;
+                        constrained.push(c);
+                    }
+                    None => constrained.push(c),
+                },
+                c if constraints.is_allowed(c) => match &mut available_chars {
+                    Some(0) => break,
+                    Some(available) => {
+                        *available -= 1;
+                        constrained.push(c);
+                    }
+                    None => constrained.push(c),
+                },
+                _ => {}
+            }
+        }
+
+        constrained
+    }
+
+    /// Returns the [`Action`]s that insert the given text at the caret,
+    /// replacing the selection, within the given [`Constraints`].
+    ///
+    /// Nothing is inserted if the [`Constraints`] reject the whole text.
+    pub(super) fn insert_actions(&self, text: &str, constraints: &Constraints<'_>) -> Vec<Action> {
+        if let Some(mask) = constraints.mask {
+            return self.insert_masked(text, constraints, mask);
+        }
+
+        let constrained = if constraints.is_empty() {
+            text.to_owned()
+        } else {
+            self.constrain(text, constraints)
+        };
+
+        let mut chars = constrained.chars();
+
+        match (chars.next(), chars.next()) {
+            (None, _) => Vec::new(),
+            (Some('\n'), None) => vec![Action::Edit(Edit::Enter)],
+            (Some(c), None) => vec![Action::Edit(Edit::Insert(c))],
+            _ => vec![Action::Edit(Edit::Paste(Arc::new(constrained)))],
+        }
+    }
+
+    /// Returns the [`Action`]s that delete the selection or, if there is
+    /// none, a single character next to the caret, in the direction of the
+    /// given [`Edit`], within the given [`Constraints`].
+    ///
+    /// A masked text is reformatted after the deletion, and the literals of
+    /// its [`InputMask`] are skipped over.
+    pub(super) fn delete_actions(&self, edit: Edit, constraints: &Constraints<'_>) -> Vec<Action> {
+        let Some(mask) = constraints.mask else {
+            return vec![Action::Edit(edit)];
+        };
+
+        let line = self.masked_line();
+        let (start, end) = self.masked_range(&line);
+
+        // Every character of a formatted text fills the slot at its index
+        let is_slot = |index: usize| {
+            mask.slots
+                .get(index)
+                .is_some_and(|slot| !matches!(slot, Slot::Literal(_)))
+        };
+
+        let (start, end) = if start < end {
+            (start, end)
+        } else {
+            let mut chars = line.char_indices().enumerate();
+
+            let deleted = if edit == Edit::Backspace {
+                chars
+                    .take_while(|(_, (offset, _))| *offset < start)
+                    .filter(|(index, _)| is_slot(*index))
+                    .last()
+            } else {
+                chars.find(|(index, (offset, _))| *offset >= start && is_slot(*index))
+            };
+
+            let Some((_, (offset, c))) = deleted else {
+                return Vec::new();
+            };
+
+            (offset, offset + c.len_utf8())
+        };
+
+        let input: Vec<char> = line[..start].chars().chain(line[end..].chars()).collect();
+        let prefix = line[..start].chars().count();
+
+        self.reformat(&line, mask, &input, prefix)
+    }
+
+    /// Returns the [`Action`]s that insert the given text in the first line
+    /// of the [`Content`], reformatting it with the [`InputMask`].
+    fn insert_masked(
+        &self,
+        text: &str,
+        constraints: &Constraints<'_>,
+        mask: &InputMask,
+    ) -> Vec<Action> {
+        let line = self.masked_line();
+        let (start, end) = self.masked_range(&line);
+
+        let inserted = text.chars().filter(|c| constraints.is_allowed(*c));
+
+        let input: Vec<char> = line[..start].chars().chain(inserted).collect();
+        let prefix = input.len();
+        let input: Vec<char> = input.into_iter().chain(line[end..].chars()).collect();
+
+        self.reformat(&line, mask, &input, prefix)
+    }
+
+    /// Returns the text of the first line of the [`Content`], the only one
+    /// of a masked text.
+    fn masked_line(&self) -> String {
+        self.line(0)
+            .map(|line| line.text.into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Returns the range of bytes of the selection in the given first line
+    /// of the [`Content`], or the empty range at its caret.
+    fn masked_range(&self, line: &str) -> (usize, usize) {
+        let column = |(index, column): (usize, usize)| {
+            if index == 0 {
+                column.min(line.len())
+            } else {
+                line.len()
+            }
+        };
+
+        match self.selection_bounds() {
+            Some((start, end)) => (column(start), column(end)),
+            None => {
+                let caret = column(self.cursor_position());
+
+                (caret, caret)
+            }
+        }
+    }
+
+    /// Returns the [`Action`]s that turn the given first line of the
+    /// [`Content`] into the given input formatted with the [`InputMask`],
+    /// leaving the caret after the given amount of characters of the input.
+    ///
+    /// Pasting leaves the caret at the end of the pasted text, so the part
+    /// after the caret replaces the whole text first, and the part before it
+    /// is pasted at its start.
+    fn reformat(&self, line: &str, mask: &InputMask, input: &[char], prefix: usize) -> Vec<Action> {
+        let (formatted, caret) = mask.format(input, prefix);
+
+        if formatted == line {
+            return Vec::new();
+        }
+
+        let caret = formatted
+            .char_indices()
+            .nth(caret)
+            .map_or(formatted.len(), |(index, _)| index);
+
+        let (before, after) = formatted.split_at(caret);
+
+        if after.is_empty() {
+            return vec![Action::SelectAll, replace(before)];
+        }
+
+        let mut actions = vec![
+            Action::SelectAll,
+            replace(after),
+            Action::Move(Motion::DocumentStart),
+        ];
+
+        if !before.is_empty() {
+            actions.push(replace(before));
+        }
+
+        actions
+    }
+}
+
+/// Returns the [`Action`] that replaces the selection with the given text.
+fn replace(text: &str) -> Action {
+    if text.is_empty() {
+        Action::Edit(Edit::Delete)
+    } else {
+        Action::Edit(Edit::Paste(Arc::new(text.to_owned())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Content = super::Content<iced::Renderer>;
+
+    fn content(text: &str, column: usize) -> Content {
+        let mut content = Content::with_text(text);
+
+        content.perform(Action::Move(Motion::DocumentStart));
+
+        for _ in 0..column {
+            content.perform(Action::Move(Motion::Right));
+        }
+
+        content
+    }
+
+    fn format(pattern: &str, input: &str, prefix: usize) -> (String, usize) {
+        let input: Vec<char> = input.chars().collect();
+
+        InputMask::new(pattern).format(&input, prefix)
+    }
+
+    #[test]
+    fn formats_complete_input() {
+        assert_eq!(
+            format("(999) 999-9999", "5551234567", 10),
+            ("(555) 123-4567".to_owned(), 14)
+        );
+    }
+
+    #[test]
+    fn keeps_literals_of_the_input() {
+        assert_eq!(
+            format("(999) 999-9999", "(555) 123", 9),
+            ("(555) 123-".to_owned(), 10)
+        );
+    }
+
+    #[test]
+    fn inserts_literals_once_the_input_reaches_them() {
+        assert_eq!(format("(999) 999-9999", "", 0), (String::new(), 0));
+        assert_eq!(format("(999) 999-9999", "55", 2), ("(55".to_owned(), 3));
+        assert_eq!(format("(999) 999-9999", "555", 3), ("(555) ".to_owned(), 6));
+    }
+
+    #[test]
+    fn drops_input_that_does_not_fit() {
+        assert_eq!(format("9999-99-99", "20x24", 5), ("2024-".to_owned(), 5));
+        assert_eq!(format("aa-99", "a1b2", 4), ("ab-2".to_owned(), 4));
+    }
+
+    #[test]
+    fn places_the_caret_after_the_prefix() {
+        assert_eq!(
+            format("(999) 999-9999", "5551", 2),
+            ("(555) 1".to_owned(), 3)
+        );
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_mask() {
+        assert_eq!(format("99", "12345", 5), ("12".to_owned(), 2));
+    }
+
+    #[test]
+    fn escapes_literals() {
+        assert_eq!(format("\\9-9", "3", 1), ("9-3".to_owned(), 3));
+    }
+
+    #[test]
+    fn truncates_to_the_available_chars() {
+        let mut content = content("abc", 3);
+        let constraints = Constraints {
+            max_chars: Some(5),
+            ..Constraints::default()
+        };
+
+        for action in content.insert_actions("xyz", &constraints) {
+            content.perform(action);
+        }
+
+        assert_eq!(content.text(), "abcxy");
+
+        content.perform(Action::SelectAll);
+
+        for action in content.insert_actions("123456", &constraints) {
+            content.perform(action);
+        }
+
+        assert_eq!(content.text(), "12345");
+    }
+
+    #[test]
+    fn typing_replaces_the_masked_value() {
+        let mask = InputMask::new("(999) 999-9999");
+        let constraints = Constraints {
+            mask: Some(&mask),
+            ..Constraints::default()
+        };
+
+        let mut content = content("(555) 12", 8);
+        let actions = content.insert_actions("3", &constraints);
+
+        assert_eq!(actions.len(), 2);
+
+        for action in actions {
+            content.perform(action);
+        }
+
+        assert_eq!(content.text(), "(555) 123-");
+        assert_eq!(content.cursor_position(), (0, 10));
+    }
+
+    #[test]
+    fn deleting_keeps_the_caret_in_the_masked_value() {
+        let mask = InputMask::new("(999) 999-9999");
+        let constraints = Constraints {
+            mask: Some(&mask),
+            ..Constraints::default()
+        };
+
+        let mut content = content("(555) 123-4567", 7);
+
+        for action in content.delete_actions(Edit::Backspace, &constraints) {
+            content.perform(action);
+        }
+
+        assert_eq!(content.text(), "(555) 234-567");
+        assert_eq!(content.cursor_position(), (0, 6));
+
+        let mut content = self::content("(555) 123-4567", 1);
+
+        for action in content.delete_actions(Edit::Backspace, &constraints) {
+            content.perform(action);
+        }
+
+        assert_eq!(content.text(), "(555) 123-4567");
+    }
+}
@@ -0,0 +1,314 @@
+//! Show a context menu with clipboard and custom actions on right click.
+use super::{Binding, Catalog, State, Status, Target, handle_binding, highlighter};
+use crate::core::keyboard::{self, key};
+use crate::core::text::{self, Paragraph, Text};
+use crate::core::{
+    Border, Clipboard, Event, Layout, Padding, Pixels, Point, Rectangle, Shell, Size, alignment,
+    layout, mouse, overlay, renderer,
+};
+use crate::widget::text::{LineHeight, Shaping, Wrapping};
+
+/// The padding around the entries of a context menu.
+const PADDING: Padding = Padding {
+    top: 4.0,
+    right: 4.0,
+    bottom: 4.0,
+    left: 4.0,
+};
+
+/// The padding of each entry of a context menu.
+const ENTRY_PADDING: Padding = Padding {
+    top: 3.0,
+    right: 16.0,
+    bottom: 3.0,
+    left: 8.0,
+};
+
+/// An entry of the context menu of a [`TextBox`].
+///
+/// [`TextBox`]: super::TextBox
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry<Message> {
+    label: String,
+    binding: Binding<Message>,
+    needs_selection: bool,
+}
+
+impl<Message> Entry<Message> {
+    /// Creates a new [`Entry`] with the given label, which applies the given
+    /// [`Binding`] when pressed.
+    pub fn new(label: impl Into<String>, binding: Binding<Message>) -> Self {
+        Self {
+            label: label.into(),
+            binding,
+            needs_selection: false,
+        }
+    }
+
+    /// Creates a new [`Entry`] with the given label, which produces the given
+    /// message when pressed.
+    pub fn message(label: impl Into<String>, message: Message) -> Self {
+        Self::new(label, Binding::Custom(message))
+    }
+
+    /// Sets whether the [`Entry`] is only enabled when some text is selected.
+    pub fn needs_selection(mut self, needs_selection: bool) -> Self {
+        self.needs_selection = needs_selection;
+        self
+    }
+
+    /// Returns the default entries of a context menu: Cut, Copy, Paste and
+    /// Select All.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("Cut", Binding::Cut).needs_selection(true),
+            Self::new("Copy", Binding::Copy).needs_selection(true),
+            Self::new("Paste", Binding::Paste),
+            Self::new("Select All", Binding::SelectAll),
+        ]
+    }
+
+    /// Returns the label of the [`Entry`].
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the [`Binding`] applied by the [`Entry`].
+    pub fn binding(&self) -> &Binding<Message> {
+        &self.binding
+    }
+
+    /// Returns whether the [`Entry`] can be pressed.
+    pub fn is_enabled(&self, has_selection: bool) -> bool {
+        !self.needs_selection || has_selection
+    }
+}
+
+/// The overlay of an open context menu.
+pub(super) struct Overlay<'a, 'b, Link, H, Message, Theme, Renderer>
+where
+    H: highlighter::Highlighter,
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    pub(super) state: &'b mut State<Link, H, Renderer::Paragraph>,
+    pub(super) entries: &'b [Entry<Message>],
+    pub(super) has_selection: bool,
+    pub(super) target: Target<'b, Renderer, Message>,
+    pub(super) class: &'b Theme::Class<'a>,
+    pub(super) position: Point,
+    pub(super) text_size: Pixels,
+    pub(super) line_height: LineHeight,
+    pub(super) font: Renderer::Font,
+}
+
+impl<Link, H, Message, Theme, Renderer> Overlay<'_, '_, Link, H, Message, Theme, Renderer>
+where
+    H: highlighter::Highlighter,
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    fn row_height(&self) -> f32 {
+        f32::from(self.line_height.to_absolute(self.text_size)) + ENTRY_PADDING.vertical()
+    }
+
+    fn row_bounds(&self, bounds: Rectangle, index: usize) -> Rectangle {
+        let row_height = self.row_height();
+
+        Rectangle {
+            x: bounds.x + PADDING.left,
+            y: bounds.y + PADDING.top + row_height * index as f32,
+            width: bounds.width - PADDING.horizontal(),
+            height: row_height,
+        }
+    }
+
+    fn hovered_entry(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+        let position = cursor.position_in(bounds)?;
+        let index = ((position.y - PADDING.top) / self.row_height()).floor();
+
+        (index >= 0.0 && (index as usize) < self.entries.len()).then_some(index as usize)
+    }
+
+    /// Returns whether the given [`Entry`] can be pressed. A secure text box
+    /// never copies its text, so the entries that would are disabled.
+    fn is_enabled(&self, entry: &Entry<Message>) -> bool
+    where
+        Message: Clone,
+    {
+        entry.is_enabled(self.has_selection)
+            && !(self.state.is_secure && entry.binding.clone().secured().is_none())
+    }
+
+    fn close(&mut self, shell: &mut Shell<'_, Message>) {
+        self.state.context_menu = None;
+        shell.request_redraw();
+    }
+}
+
+impl<Link, H, Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for Overlay<'_, '_, Link, H, Message, Theme, Renderer>
+where
+    Link: Clone + 'static,
+    H: highlighter::Highlighter,
+    Message: std::fmt::Debug + Clone,
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    fn layout(&mut self, _renderer: &Renderer, bounds: Size) -> layout::Node {
+        let labels_width = self
+            .entries
+            .iter()
+            .map(|entry| {
+                Renderer::Paragraph::with_text(Text {
+                    content: entry.label.as_str(),
+                    bounds: Size::new(f32::INFINITY, f32::INFINITY),
+                    size: self.text_size,
+                    line_height: self.line_height,
+                    font: self.font,
+                    align_x: text::Alignment::Left,
+                    align_y: alignment::Vertical::Top,
+                    shaping: Shaping::Advanced,
+                    wrapping: Wrapping::None,
+                })
+                .min_width()
+            })
+            .fold(0.0, f32::max);
+
+        let size = Size::new(
+            labels_width + ENTRY_PADDING.horizontal() + PADDING.horizontal(),
+            self.row_height() * self.entries.len() as f32 + PADDING.vertical(),
+        );
+
+        // Keep the menu inside of the window
+        let position = Point::new(
+            self.position.x.min(bounds.width - size.width).max(0.0),
+            self.position.y.min(bounds.height - size.height).max(0.0),
+        );
+
+        layout::Node::new(size).move_to(position)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        defaults: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        let style = theme.style(self.class, Status::Focused);
+        let bounds = layout.bounds();
+        let hovered_entry = self.hovered_entry(bounds, cursor);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border: style.border,
+                ..renderer::Quad::default()
+            },
+            style.background,
+        );
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let row = self.row_bounds(bounds, index);
+            let is_enabled = self.is_enabled(entry);
+
+            if is_enabled && hovered_entry == Some(index) {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: row,
+                        border: Border {
+                            radius: style.border.radius,
+                            ..Border::default()
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    style.selection,
+                );
+            }
+
+            renderer.fill_text(
+                Text {
+                    content: entry.label.clone(),
+                    bounds: row.size(),
+                    size: self.text_size,
+                    line_height: self.line_height,
+                    font: self.font,
+                    align_x: text::Alignment::Left,
+                    align_y: alignment::Vertical::Center,
+                    shaping: Shaping::Advanced,
+                    wrapping: Wrapping::None,
+                },
+                Point::new(row.x + ENTRY_PADDING.left, row.center_y()),
+                if is_enabled {
+                    style.value.unwrap_or(defaults.text_color)
+                } else {
+                    style.placeholder
+                },
+                bounds,
+            );
+        }
+    }
+
+    fn update(
+        &mut self,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        let bounds = layout.bounds();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => {
+                if let Some(index) = self.hovered_entry(bounds, cursor) {
+                    shell.capture_event();
+
+                    let entry = &self.entries[index];
+
+                    if self.is_enabled(entry) {
+                        self.close(shell);
+
+                        handle_binding(
+                            entry.binding.clone(),
+                            &self.target,
+                            self.state,
+                            clipboard,
+                            shell,
+                        );
+                    }
+                } else if cursor.is_over(bounds) {
+                    shell.capture_event();
+                } else {
+                    self.close(shell);
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if cursor.is_over(bounds) => {
+                shell.request_redraw();
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key::Named::Escape),
+                ..
+            }) => {
+                self.close(shell);
+                shell.capture_event();
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        match self.hovered_entry(layout.bounds(), cursor) {
+            Some(index) if self.is_enabled(&self.entries[index]) => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
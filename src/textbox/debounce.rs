@@ -0,0 +1,281 @@
+//! Publish the text of a text box once it settles, or at a limited rate.
+use std::sync::atomic::{self, AtomicU64};
+use std::time::{Duration, Instant};
+
+use super::Content;
+use crate::core::text;
+
+/// How the changes of a [`Delayed`] closure are spaced out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Mode {
+    /// The text is published once it stops changing for the delay.
+    Debounce,
+    /// The text is published at most once every delay while it changes.
+    Throttle,
+}
+
+/// A closure producing a message with the text of a [`TextBox`], called
+/// some time after it changes.
+///
+/// [`TextBox`]: super::TextBox
+pub(super) struct Delayed<'a, Message> {
+    pub(super) mode: Mode,
+    pub(super) delay: Duration,
+    pub(super) on_change: Box<dyn Fn(String) -> Message + 'a>,
+}
+
+/// The outcome of polling a [`Timer`].
+pub(super) enum Poll {
+    /// There is no change to publish.
+    Idle,
+    /// There is a change to publish at the given instant.
+    Pending(Instant),
+    /// The change must be published now.
+    Due,
+}
+
+/// The amount of edits made to a [`Content`] by a [`TextBox`], leaving out
+/// the ones the application made by itself.
+///
+/// [`TextBox`]: super::TextBox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Edits {
+    /// The unique identifier of the [`Content`], so a [`Content`] replaced by
+    /// the application is not mistaken for an edited one.
+    content: u64,
+    count: u64,
+}
+
+impl Edits {
+    /// Returns the [`Edits`] of a new [`Content`].
+    pub(super) fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+
+        Self {
+            content: NEXT.fetch_add(1, atomic::Ordering::Relaxed),
+            count: 0,
+        }
+    }
+
+    /// Counts one more edit.
+    pub(super) fn increment(&mut self) {
+        self.count += 1;
+    }
+}
+
+/// Tracks the [`Edits`] of a [`Content`] to know when they must be
+/// published.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Timer {
+    edits: Option<Edits>,
+    changed_at: Option<Instant>,
+    published_at: Option<Instant>,
+}
+
+impl Timer {
+    /// Notes the current [`Edits`] of the [`Content`] and returns whether
+    /// they must be published.
+    ///
+    /// Pending changes are always due once the [`TextBox`] is unfocused.
+    ///
+    /// [`TextBox`]: super::TextBox
+    pub(super) fn poll(
+        &mut self,
+        mode: Mode,
+        delay: Duration,
+        edits: Edits,
+        now: Instant,
+        is_focused: bool,
+    ) -> Poll {
+        if self.note(edits) && (mode == Mode::Debounce || self.changed_at.is_none()) {
+            self.changed_at = Some(now);
+        }
+
+        let Some(changed_at) = self.changed_at else {
+            return Poll::Idle;
+        };
+
+        let due = match mode {
+            Mode::Debounce => changed_at + delay,
+            // The first change of a while is published right away
+            Mode::Throttle => self
+                .published_at
+                .map_or(changed_at, |published_at| published_at + delay),
+        };
+
+        if now >= due || !is_focused {
+            self.changed_at = None;
+            self.published_at = Some(now);
+
+            Poll::Due
+        } else {
+            Poll::Pending(due)
+        }
+    }
+
+    /// Notes the current [`Edits`] of the [`Content`] and returns whether
+    /// any of them is still to be published, in which case they are
+    /// considered published now.
+    pub(super) fn flush(&mut self, edits: Edits, now: Instant) -> bool {
+        let is_pending = self.note(edits) || self.changed_at.is_some();
+
+        if is_pending {
+            self.changed_at = None;
+            self.published_at = Some(now);
+        }
+
+        is_pending
+    }
+
+    /// Notes the current [`Edits`], returning true if there are new ones
+    /// for the same [`Content`].
+    fn note(&mut self, edits: Edits) -> bool {
+        self.edits
+            .replace(edits)
+            .is_some_and(|seen| seen.content == edits.content && seen.count != edits.count)
+    }
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Returns the [`Edits`] of the [`Content`].
+    pub(super) fn edits(&self) -> Edits {
+        self.0.borrow().edits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(300);
+
+    fn edited(edits: Edits, count: u64) -> Edits {
+        Edits { count, ..edits }
+    }
+
+    fn is_due(poll: Poll) -> bool {
+        matches!(poll, Poll::Due)
+    }
+
+    #[test]
+    fn debounce_waits_for_the_edits_to_settle() {
+        let mut timer = Timer::default();
+        let edits = Edits::new();
+        let now = Instant::now();
+
+        assert!(matches!(
+            timer.poll(Mode::Debounce, DELAY, edits, now, true),
+            Poll::Idle
+        ));
+
+        let later = now + DELAY / 2;
+
+        assert!(matches!(
+            timer.poll(Mode::Debounce, DELAY, edited(edits, 1), later, true),
+            Poll::Pending(due) if due == later + DELAY
+        ));
+        assert!(!is_due(timer.poll(
+            Mode::Debounce,
+            DELAY,
+            edited(edits, 2),
+            later + DELAY / 2,
+            true
+        )));
+        assert!(is_due(timer.poll(
+            Mode::Debounce,
+            DELAY,
+            edited(edits, 2),
+            later + DELAY * 2,
+            true
+        )));
+        assert!(matches!(
+            timer.poll(
+                Mode::Debounce,
+                DELAY,
+                edited(edits, 2),
+                later + DELAY * 3,
+                true
+            ),
+            Poll::Idle
+        ));
+    }
+
+    #[test]
+    fn throttle_publishes_the_first_edit_right_away() {
+        let mut timer = Timer::default();
+        let edits = Edits::new();
+        let now = Instant::now();
+
+        let _ = timer.poll(Mode::Throttle, DELAY, edits, now, true);
+
+        assert!(is_due(timer.poll(
+            Mode::Throttle,
+            DELAY,
+            edited(edits, 1),
+            now,
+            true
+        )));
+        assert!(matches!(
+            timer.poll(Mode::Throttle, DELAY, edited(edits, 2), now + DELAY / 2, true),
+            Poll::Pending(due) if due == now + DELAY
+        ));
+        assert!(is_due(timer.poll(
+            Mode::Throttle,
+            DELAY,
+            edited(edits, 2),
+            now + DELAY,
+            true
+        )));
+    }
+
+    #[test]
+    fn pending_edits_are_due_when_unfocused() {
+        let mut timer = Timer::default();
+        let edits = Edits::new();
+        let now = Instant::now();
+
+        let _ = timer.poll(Mode::Debounce, DELAY, edits, now, true);
+
+        assert!(is_due(timer.poll(
+            Mode::Debounce,
+            DELAY,
+            edited(edits, 1),
+            now,
+            false
+        )));
+    }
+
+    #[test]
+    fn flush_publishes_pending_edits_once() {
+        let mut timer = Timer::default();
+        let edits = Edits::new();
+        let now = Instant::now();
+
+        let _ = timer.poll(Mode::Debounce, DELAY, edits, now, true);
+        let _ = timer.poll(Mode::Debounce, DELAY, edited(edits, 1), now, true);
+
+        assert!(timer.flush(edited(edits, 2), now));
+        assert!(!timer.flush(edited(edits, 2), now));
+        assert!(matches!(
+            timer.poll(Mode::Debounce, DELAY, edited(edits, 2), now + DELAY, true),
+            Poll::Idle
+        ));
+    }
+
+    #[test]
+    fn replaced_content_is_not_an_edit() {
+        let mut timer = Timer::default();
+        let now = Instant::now();
+
+        let _ = timer.poll(Mode::Debounce, DELAY, edited(Edits::new(), 3), now, true);
+
+        assert!(matches!(
+            timer.poll(Mode::Debounce, DELAY, Edits::new(), now, false),
+            Poll::Idle
+        ));
+        assert!(!timer.flush(Edits::new(), now));
+    }
+}
@@ -0,0 +1,142 @@
+//! Drag and drop selected text within and across text boxes.
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use super::{Action, Cursor, Edit};
+use crate::core::keyboard;
+use crate::core::{Point, Rectangle};
+
+thread_local! {
+    /// The text being dragged, shared by every text box of the application.
+    static PAYLOAD: RefCell<Option<Payload>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Payload {
+    pub(super) text: String,
+    dropped: Option<Outcome>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Move,
+    Copy,
+}
+
+impl Payload {
+    /// Returns true if the text was moved to some other text box, and must
+    /// therefore be removed from its source.
+    pub(super) fn is_moved(&self) -> bool {
+        self.dropped == Some(Outcome::Move)
+    }
+}
+
+/// The selection of a text box being dragged.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Source {
+    ranges: Vec<Rectangle>,
+    /// Whether the mouse was released outside of the source text box, which
+    /// must then wait for the drop to be resolved by some other text box.
+    pub(super) is_released: bool,
+}
+
+impl Source {
+    /// Grabs the current selection, if the given position is inside of it.
+    pub(super) fn grab(cursor: Cursor, position: Point) -> Option<Self> {
+        let Cursor::Selection(ranges) = cursor else {
+            return None;
+        };
+
+        ranges
+            .iter()
+            .any(|range| range.contains(position))
+            .then_some(Self {
+                ranges,
+                is_released: false,
+            })
+    }
+
+    /// Returns true if the given position is inside of the dragged selection.
+    pub(super) fn contains(&self, position: Point) -> bool {
+        self.ranges.iter().any(|range| range.contains(position))
+    }
+
+    /// Returns the [`Action`]s that drop the given text at the `target`
+    /// position of the source text box, removing the dragged selection unless
+    /// it is being copied.
+    ///
+    /// Since actions are positional, the edit that is furthest into the text
+    /// is always performed first, so the other one is not displaced.
+    pub(super) fn drop(&self, target: Point, text: String, is_copy: bool) -> Vec<Action> {
+        let paste = Action::Edit(Edit::Paste(Arc::new(text)));
+
+        let (Some(first), Some(last)) = (self.ranges.first(), self.ranges.last()) else {
+            return Vec::new();
+        };
+
+        if is_copy {
+            vec![Action::Click(target), paste]
+        } else if target.y < first.y || (target.y < first.y + first.height && target.x < first.x) {
+            vec![Action::Edit(Edit::Delete), Action::Click(target), paste]
+        } else {
+            vec![
+                Action::Click(target),
+                paste,
+                Action::Click(Point::new(first.x, first.center_y())),
+                Action::Drag(Point::new(last.x + last.width, last.center_y())),
+                Action::Edit(Edit::Delete),
+            ]
+        }
+    }
+}
+
+/// Starts dragging the given text.
+pub(super) fn start(text: String) {
+    PAYLOAD.with_borrow_mut(|payload| {
+        *payload = Some(Payload {
+            text,
+            dropped: None,
+        });
+    });
+}
+
+/// Returns true if some text is being dragged and has not been dropped yet.
+pub(super) fn is_active() -> bool {
+    PAYLOAD.with_borrow(|payload| {
+        payload
+            .as_ref()
+            .is_some_and(|payload| payload.dropped.is_none())
+    })
+}
+
+/// Drops the dragged text onto another text box, returning it if it was not
+/// dropped already.
+pub(super) fn drop(is_copy: bool) -> Option<String> {
+    PAYLOAD.with_borrow_mut(|payload| {
+        let payload = payload
+            .as_mut()
+            .filter(|payload| payload.dropped.is_none())?;
+
+        payload.dropped = Some(if is_copy {
+            Outcome::Copy
+        } else {
+            Outcome::Move
+        });
+
+        Some(payload.text.clone())
+    })
+}
+
+/// Finishes the current drag, returning its [`Payload`].
+pub(super) fn finish() -> Option<Payload> {
+    PAYLOAD.with_borrow_mut(Option::take)
+}
+
+/// Returns true if the given modifiers turn a move into a copy.
+pub(super) fn is_copy(modifiers: keyboard::Modifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.alt()
+    } else {
+        modifiers.control()
+    }
+}
@@ -0,0 +1,487 @@
+//! Edit the words and lines of a [`Content`] by composing [`Action`]s.
+use std::sync::Arc;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Action, Content, Edit, LineEnding, Motion};
+use crate::core::text;
+
+/// A word or line editing command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Command {
+    DeleteWordBackward,
+    DeleteWordForward,
+    DeleteToLineStart,
+    DeleteToLineEnd,
+    DuplicateLine,
+    DuplicateLineUp,
+    MoveLineUp,
+    MoveLineDown,
+    JoinLines,
+    TransposeChars,
+    SelectLines(usize),
+    MoveLeftInLine,
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Deletes the word before the caret, or the current selection.
+    pub fn delete_word_backward(&mut self) {
+        self.perform_command(Command::DeleteWordBackward);
+    }
+
+    /// Deletes the word after the caret, or the current selection.
+    pub fn delete_word_forward(&mut self) {
+        self.perform_command(Command::DeleteWordForward);
+    }
+
+    /// Deletes the text between the start of the line and the caret, or the
+    /// current selection.
+    pub fn delete_to_line_start(&mut self) {
+        self.perform_command(Command::DeleteToLineStart);
+    }
+
+    /// Deletes the text between the caret and the end of the line, or the
+    /// current selection.
+    pub fn delete_to_line_end(&mut self) {
+        self.perform_command(Command::DeleteToLineEnd);
+    }
+
+    /// Duplicates the line of the caret, moving the caret to the copy below.
+    pub fn duplicate_line(&mut self) {
+        self.perform_command(Command::DuplicateLine);
+    }
+
+    /// Duplicates the line of the caret, keeping the caret in the copy above.
+    pub fn duplicate_line_up(&mut self) {
+        self.perform_command(Command::DuplicateLineUp);
+    }
+
+    /// Swaps the line of the caret with the line above it, leaving the caret
+    /// at the end of the moved line.
+    pub fn move_line_up(&mut self) {
+        self.perform_command(Command::MoveLineUp);
+    }
+
+    /// Swaps the line of the caret with the line below it, leaving the caret
+    /// at the end of the moved line.
+    pub fn move_line_down(&mut self) {
+        self.perform_command(Command::MoveLineDown);
+    }
+
+    /// Joins the line of the caret with the line below it, separating them
+    /// with a single space and leaving the caret at the end of the result.
+    pub fn join_lines(&mut self) {
+        self.perform_command(Command::JoinLines);
+    }
+
+    /// Swaps the characters around the caret, or the two characters before
+    /// it at the end of a line.
+    pub fn transpose_chars(&mut self) {
+        self.perform_command(Command::TransposeChars);
+    }
+
+    /// Selects the given amount of whole lines, starting from the line of
+    /// the caret, without their last line break.
+    pub fn select_lines(&mut self, count: usize) {
+        self.perform_command(Command::SelectLines(count));
+    }
+
+    /// Moves the caret one character to the left, unless it is at the start
+    /// of its line.
+    pub fn move_left_in_line(&mut self) {
+        self.perform_command(Command::MoveLeftInLine);
+    }
+
+    fn perform_command(&mut self, command: Command) {
+        for action in self.command_actions(command) {
+            self.perform(action);
+        }
+    }
+
+    /// Returns the [`Action`]s that apply the given [`Command`] to the
+    /// current text and caret of the [`Content`].
+    ///
+    /// Only the lines around the caret are edited, by pasting over them
+    /// once. Since vertical motions are visual, two lines are selected by
+    /// crossing the line break between them instead.
+    pub(super) fn command_actions(&self, command: Command) -> Vec<Action> {
+        let has_selection = self.selection().is_some();
+        let (line, column) = self.cursor_position();
+
+        let (text, ending) = self
+            .line(line)
+            .map(|line| (line.text.into_owned(), separator(line.ending)))
+            .unwrap_or_default();
+
+        let (before, after) = text.split_at_checked(column).unwrap_or((&text, ""));
+
+        let other = |line: usize| {
+            self.line(line)
+                .map(|line| (line.text.into_owned(), separator(line.ending)))
+        };
+
+        match command {
+            Command::DeleteWordBackward => delete(has_selection, Motion::WordLeft, Edit::Backspace),
+            Command::DeleteWordForward => delete(has_selection, Motion::WordRight, Edit::Delete),
+            Command::DeleteToLineStart => delete(has_selection, Motion::Home, Edit::Backspace),
+            Command::DeleteToLineEnd => delete(has_selection, Motion::End, Edit::Delete),
+            Command::DuplicateLine if !has_selection => {
+                // Pasting the rest of the line before its start leaves the
+                // caret at the same column of the copy
+                vec![paste(format!("{after}\n{before}"))]
+            }
+            Command::DuplicateLine => vec![
+                Action::SelectLine,
+                Action::Move(Motion::Right),
+                paste(format!("{ending}{text}")),
+            ],
+            Command::DuplicateLineUp => vec![
+                Action::SelectLine,
+                Action::Move(Motion::Left),
+                paste(format!("{text}{ending}")),
+                Action::Move(Motion::Left),
+            ],
+            Command::MoveLineUp if line > 0 => {
+                let Some((previous, separator)) = other(line - 1) else {
+                    return Vec::new();
+                };
+
+                let mut actions = select_with_previous();
+                actions.push(paste(format!("{text}{separator}{previous}")));
+
+                // Leave the caret at the end of the moved line
+                actions.extend([
+                    Action::SelectLine,
+                    Action::Move(Motion::Left),
+                    Action::Move(Motion::Left),
+                ]);
+
+                actions
+            }
+            Command::MoveLineDown => {
+                let Some((next, _)) = other(line + 1) else {
+                    return Vec::new();
+                };
+
+                let mut actions = select_with_next();
+                actions.push(paste(format!("{next}{ending}{text}")));
+
+                actions
+            }
+            Command::JoinLines => {
+                let Some((next, _)) = other(line + 1) else {
+                    return Vec::new();
+                };
+
+                // Drop the spaces around the line break
+                let trimmed = text.trim_end();
+                let next_trimmed = next.trim_start();
+
+                let joined = if trimmed.is_empty() || next_trimmed.is_empty() {
+                    format!("{trimmed}{next_trimmed}")
+                } else {
+                    format!("{trimmed} {next_trimmed}")
+                };
+
+                let mut actions = select_with_next();
+                actions.push(paste(joined));
+
+                actions
+            }
+            Command::TransposeChars if !has_selection => {
+                let mut graphemes = before.graphemes(true).rev();
+
+                match (
+                    graphemes.next(),
+                    graphemes.next(),
+                    after.graphemes(true).next(),
+                ) {
+                    (Some(previous), _, Some(next)) => vec![
+                        Action::Move(Motion::Left),
+                        Action::Select(Motion::Right),
+                        Action::Select(Motion::Right),
+                        paste(format!("{next}{previous}")),
+                    ],
+                    (Some(last), Some(second_last), None) => vec![
+                        Action::Select(Motion::Left),
+                        Action::Select(Motion::Left),
+                        paste(format!("{last}{second_last}")),
+                    ],
+                    _ => Vec::new(),
+                }
+            }
+            Command::SelectLines(count) if count > 0 => {
+                let lines = (line + 1..line + count).map_while(&other).count();
+
+                // Extend a line selection from the last row of the line, so
+                // every step down reaches the next line
+                [
+                    Action::SelectLine,
+                    Action::Move(Motion::Right),
+                    Action::SelectLine,
+                ]
+                .into_iter()
+                .chain(std::iter::repeat_n(Action::Select(Motion::Down), lines))
+                .collect()
+            }
+            Command::MoveLeftInLine if has_selection || column > 0 => {
+                vec![Action::Move(Motion::Left)]
+            }
+            Command::MoveLineUp
+            | Command::TransposeChars
+            | Command::SelectLines(_)
+            | Command::MoveLeftInLine => Vec::new(),
+        }
+    }
+
+    /// Returns the text between the caret and the end of its line, or its
+    /// line break if the caret is already there, along with the [`Action`]s
+    /// that delete it.
+    pub(super) fn kill_to_line_end(&self) -> Option<(String, Vec<Action>)> {
+        let (line, column) = self.cursor_position();
+        let current = self.line(line)?;
+
+        let (before, after) = current
+            .text
+            .split_at_checked(column)
+            .unwrap_or((&current.text, ""));
+
+        if !after.is_empty() {
+            // Replacing the whole line leaves the caret right where it was
+            Some((
+                after.to_owned(),
+                vec![Action::SelectLine, paste(before.to_owned())],
+            ))
+        } else if line + 1 < self.line_count() {
+            let ending = if current.ending == LineEnding::None {
+                LineEnding::default()
+            } else {
+                current.ending
+            };
+
+            Some((ending.as_str().to_owned(), vec![Action::Edit(Edit::Delete)]))
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the [`Action`]s that delete the selection or, if there is none,
+/// the text covered by the given [`Motion`].
+fn delete(has_selection: bool, motion: Motion, edit: Edit) -> Vec<Action> {
+    if has_selection {
+        vec![Action::Edit(edit)]
+    } else {
+        vec![Action::Select(motion), Action::Edit(edit)]
+    }
+}
+
+/// Returns the [`Action`]s that select the whole line of the caret along
+/// with the line above it.
+fn select_with_previous() -> Vec<Action> {
+    vec![
+        Action::SelectLine,
+        Action::Move(Motion::Left),
+        Action::Move(Motion::Left),
+        Action::SelectLine,
+        Action::Select(Motion::Right),
+    ]
+}
+
+/// Returns the [`Action`]s that select the whole line of the caret along
+/// with the line below it.
+fn select_with_next() -> Vec<Action> {
+    vec![
+        Action::SelectLine,
+        Action::Move(Motion::Right),
+        Action::Move(Motion::Right),
+        Action::SelectLine,
+        Action::Select(Motion::Left),
+    ]
+}
+
+fn paste(text: String) -> Action {
+    Action::Edit(Edit::Paste(Arc::new(text)))
+}
+
+/// Returns the line break of a line with the given [`LineEnding`].
+fn separator(ending: LineEnding) -> &'static str {
+    if ending == LineEnding::None {
+        LineEnding::default().as_str()
+    } else {
+        ending.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Content = super::Content<iced::Renderer>;
+
+    fn content(text: &str, (line, column): (usize, usize)) -> Content {
+        let mut content = Content::with_text(text);
+
+        content.perform(Action::Move(Motion::DocumentStart));
+
+        for _ in 0..line {
+            content.perform(Action::Move(Motion::Down));
+        }
+
+        for _ in 0..column {
+            content.perform(Action::Move(Motion::Right));
+        }
+
+        content
+    }
+
+    #[test]
+    fn delete_words() {
+        let mut content = content("hello world", (0, 11));
+        content.delete_word_backward();
+
+        assert_eq!(content.text(), "hello ");
+
+        let mut content = self::content("hello world", (0, 0));
+        content.delete_word_forward();
+
+        assert_eq!(content.text(), " world");
+    }
+
+    #[test]
+    fn delete_to_line_boundaries() {
+        let mut content = content("one\nhello world\ntwo", (1, 6));
+        content.delete_to_line_start();
+
+        assert_eq!(content.text(), "one\nworld\ntwo");
+        assert_eq!(content.cursor_position(), (1, 0));
+
+        let mut content = self::content("one\nhello world\ntwo", (1, 5));
+        content.delete_to_line_end();
+
+        assert_eq!(content.text(), "one\nhello\ntwo");
+        assert_eq!(content.cursor_position(), (1, 5));
+    }
+
+    #[test]
+    fn duplicate_line() {
+        let mut content = content("one\ntwo", (0, 1));
+        content.duplicate_line();
+
+        assert_eq!(content.text(), "one\none\ntwo");
+        assert_eq!(content.cursor_position(), (1, 1));
+
+        let mut content = self::content("one\ntwo", (1, 1));
+        content.perform(Action::Select(Motion::Right));
+        content.duplicate_line();
+
+        assert_eq!(content.text(), "one\ntwo\ntwo");
+        assert_eq!(content.cursor_position(), (2, 3));
+    }
+
+    #[test]
+    fn duplicate_line_up() {
+        let mut content = content("one\ntwo", (1, 1));
+        content.duplicate_line_up();
+
+        assert_eq!(content.text(), "one\ntwo\ntwo");
+        assert_eq!(content.cursor_position(), (1, 3));
+    }
+
+    #[test]
+    fn move_line_up() {
+        let mut content = content("one\ntwo\nthree", (1, 1));
+        content.move_line_up();
+
+        assert_eq!(content.text(), "two\none\nthree");
+        assert_eq!(content.cursor_position(), (0, 3));
+
+        content.move_line_up();
+
+        assert_eq!(content.text(), "two\none\nthree");
+    }
+
+    #[test]
+    fn move_line_down() {
+        let mut content = content("one\ntwo\nthree", (1, 1));
+        content.move_line_down();
+
+        assert_eq!(content.text(), "one\nthree\ntwo");
+        assert_eq!(content.cursor_position(), (2, 3));
+
+        content.move_line_down();
+
+        assert_eq!(content.text(), "one\nthree\ntwo");
+    }
+
+    #[test]
+    fn moving_long_lines_takes_a_few_actions() {
+        let long = "a".repeat(10_000);
+        let content = content(&format!("{long}\n{long}"), (0, 5));
+
+        assert!(content.command_actions(Command::MoveLineDown).len() < 10);
+        assert!(content.command_actions(Command::JoinLines).len() < 10);
+    }
+
+    #[test]
+    fn join_lines() {
+        let mut content = content("one  \n   two\nthree", (0, 0));
+        content.join_lines();
+
+        assert_eq!(content.text(), "one two\nthree");
+        assert_eq!(content.cursor_position(), (0, 7));
+
+        let mut content = self::content("\n  two", (0, 0));
+        content.join_lines();
+
+        assert_eq!(content.text(), "two");
+    }
+
+    #[test]
+    fn transpose_chars() {
+        let mut content = content("abc", (0, 1));
+        content.transpose_chars();
+
+        assert_eq!(content.text(), "bac");
+        assert_eq!(content.cursor_position(), (0, 2));
+
+        let mut content = self::content("abc", (0, 3));
+        content.transpose_chars();
+
+        assert_eq!(content.text(), "acb");
+        assert_eq!(content.cursor_position(), (0, 3));
+
+        let mut content = self::content("abc", (0, 0));
+        content.transpose_chars();
+
+        assert_eq!(content.text(), "abc");
+    }
+
+    #[test]
+    fn select_lines() {
+        let mut content = content("one\ntwo\nthree\nfour", (1, 1));
+        content.select_lines(2);
+
+        assert_eq!(content.selection().as_deref(), Some("two\nthree"));
+
+        let mut content = self::content("one\ntwo\nthree\nfour", (1, 1));
+        content.select_lines(10);
+
+        assert_eq!(content.selection().as_deref(), Some("two\nthree\nfour"));
+    }
+
+    #[test]
+    fn move_left_in_line() {
+        let mut content = content("one\ntwo", (1, 1));
+        content.move_left_in_line();
+
+        assert_eq!(content.cursor_position(), (1, 0));
+
+        content.move_left_in_line();
+
+        assert_eq!(content.cursor_position(), (1, 0));
+    }
+}
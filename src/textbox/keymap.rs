@@ -0,0 +1,853 @@
+//! Use stateful key binding presets, like modal editing or a kill ring, or
+//! declare key bindings in a settings file.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use super::{Binding, CaretShape, Direction, KeyPress, Motion, Status};
+use crate::core::keyboard::{self, key};
+
+mod shortcut;
+
+pub use shortcut::{Chord, ParseError, Shortcut};
+
+/// The largest count of a [`Vim`] command, so a mistyped count cannot
+/// repeat it endlessly.
+const MAX_COUNT: usize = 10_000;
+
+/// A set of key bindings that keeps its own state between key presses.
+///
+/// The state lives in the keymap itself, which is borrowed by the [`TextBox`],
+/// so it must rely on interior mutability.
+///
+/// [`TextBox`]: super::TextBox
+pub trait Stateful<Message> {
+    /// Returns the [`Binding`] for the given [`KeyPress`], updating the state
+    /// of the keymap.
+    fn key_press(&self, key_press: KeyPress) -> Option<Binding<Message>>;
+
+    /// Returns the [`CaretShape`] required by the current state of the keymap,
+    /// if any.
+    fn caret_shape(&self) -> Option<CaretShape> {
+        None
+    }
+}
+
+/// A declarative set of key bindings, which can be (de)serialized with the
+/// `serde` feature.
+///
+/// Each [`Chord`] maps to the name of a built-in [`Binding`], as accepted
+/// by [`Binding::from_name`], or to the name of a custom action of the
+/// application. The `none` name unbinds a shortcut.
+///
+/// The chords of the current platform override the shared ones. Chords of
+/// several key presses must be given to [`TextBox::chords`] with
+/// [`Keymap::chords`]. In TOML:
+///
+/// ```toml
+/// [bindings]
+/// "ctrl+k" = "kill-to-line-end"
+/// "cmd+shift+z" = "redo"
+/// "ctrl+k ctrl+c" = "comment"
+///
+/// [macos]
+/// "cmd+backspace" = "delete-to-line-start"
+/// ```
+///
+/// [`TextBox::chords`]: super::TextBox::chords
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Keymap {
+    bindings: BTreeMap<Chord, String>,
+    macos: BTreeMap<Chord, String>,
+    linux: BTreeMap<Chord, String>,
+    windows: BTreeMap<Chord, String>,
+}
+
+/// A platform with its own shortcuts in a [`Keymap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// macOS.
+    MacOS,
+    /// Linux.
+    Linux,
+    /// Windows.
+    Windows,
+}
+
+impl Platform {
+    /// Returns the current [`Platform`], if it has its own shortcuts.
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Self::MacOS)
+        } else if cfg!(target_os = "linux") {
+            Some(Self::Linux)
+        } else if cfg!(target_os = "windows") {
+            Some(Self::Windows)
+        } else {
+            None
+        }
+    }
+}
+
+impl Keymap {
+    /// Creates an empty [`Keymap`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the [`Chord`] to the named action on every platform.
+    pub fn bind(mut self, chord: impl Into<Chord>, name: impl Into<String>) -> Self {
+        let _ = self.bindings.insert(chord.into(), name.into());
+        self
+    }
+
+    /// Binds the [`Chord`] to the named action on the given [`Platform`].
+    pub fn bind_on(
+        mut self,
+        platform: Platform,
+        chord: impl Into<Chord>,
+        name: impl Into<String>,
+    ) -> Self {
+        let _ = self
+            .platform_mut(platform)
+            .insert(chord.into(), name.into());
+        self
+    }
+
+    /// Returns the name of the action bound to the given [`KeyPress`] alone,
+    /// if any.
+    pub fn action(&self, key_press: &KeyPress) -> Option<&str> {
+        self.current()
+            .find(|(chord, _)| chord.matches(std::slice::from_ref(key_press)) == Some(true))
+            .map(|(_, name)| name)
+    }
+
+    /// Returns the [`Chord`]s of several key presses of the [`Keymap`] for
+    /// the current platform, along with their [`Binding`].
+    ///
+    /// Custom actions are turned into messages with the given closure.
+    pub fn chords<Message>(
+        &self,
+        custom: impl Fn(&str) -> Option<Message>,
+    ) -> Vec<(Chord, Binding<Message>)> {
+        let mut chords: Vec<(Chord, Binding<Message>)> = Vec::new();
+
+        for (chord, name) in self.current() {
+            if chord.shortcuts().len() < 2 || chords.iter().any(|(other, _)| other == chord) {
+                continue;
+            }
+
+            if let Some(binding) =
+                Binding::from_name(name).or_else(|| custom(name).map(Binding::Custom))
+            {
+                chords.push((chord.clone(), binding));
+            }
+        }
+
+        chords
+    }
+
+    /// Returns the chords of the current platform, followed by the shared ones.
+    fn current(&self) -> impl Iterator<Item = (&Chord, &str)> {
+        Platform::current()
+            .map(|platform| self.platform(platform))
+            .into_iter()
+            .chain([&self.bindings])
+            .flatten()
+            .map(|(chord, name)| (chord, name.as_str()))
+    }
+
+    /// Resolves the given [`KeyPress`] to a [`Binding`], turning custom
+    /// actions into messages with the given closure.
+    ///
+    /// Key presses without a shortcut fall back to [`Binding::from_key_press`].
+    pub fn resolve<Message>(
+        &self,
+        key_press: KeyPress,
+        custom: impl Fn(&str) -> Option<Message>,
+    ) -> Option<Binding<Message>> {
+        if key_press.status != Status::Focused {
+            return None;
+        }
+
+        match self.action(&key_press) {
+            Some("none") => None,
+            Some(name) => Binding::from_name(name).or_else(|| custom(name).map(Binding::Custom)),
+            None => Binding::from_key_press(key_press),
+        }
+    }
+
+    /// Returns a closure for [`TextBox::key_binding`] that resolves key
+    /// presses with the [`Keymap`].
+    ///
+    /// [`TextBox::key_binding`]: super::TextBox::key_binding
+    pub fn key_binding<'a, Message>(
+        &'a self,
+        custom: impl Fn(&str) -> Option<Message> + 'a,
+    ) -> impl Fn(KeyPress) -> Option<Binding<Message>> + 'a {
+        move |key_press| self.resolve(key_press, &custom)
+    }
+
+    fn platform(&self, platform: Platform) -> &BTreeMap<Chord, String> {
+        match platform {
+            Platform::MacOS => &self.macos,
+            Platform::Linux => &self.linux,
+            Platform::Windows => &self.windows,
+        }
+    }
+
+    fn platform_mut(&mut self, platform: Platform) -> &mut BTreeMap<Chord, String> {
+        match platform {
+            Platform::MacOS => &mut self.macos,
+            Platform::Linux => &mut self.linux,
+            Platform::Windows => &mut self.windows,
+        }
+    }
+}
+
+/// A modal, Vim-style keymap.
+///
+/// It supports normal, insert and visual modes; the `hjkl`, `w`/`b`/`e`,
+/// `0`/`$` and `gg`/`G` motions; the `d`, `c` and `y` operators followed by a
+/// motion, or doubled for whole lines; and counts for both.
+///
+/// Word motions are approximated with the word boundaries of the editor.
+#[derive(Debug, Default)]
+pub struct Vim {
+    state: RefCell<VimState>,
+}
+
+/// The mode of a [`Vim`] keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Keys move the caret and apply operators.
+    #[default]
+    Normal,
+    /// Keys insert text.
+    Insert,
+    /// Keys extend the selection.
+    Visual,
+}
+
+#[derive(Debug, Default)]
+struct VimState {
+    mode: Mode,
+    count: Option<usize>,
+    operator: Option<Operator>,
+    is_g_pending: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+impl Vim {
+    /// Creates a new [`Vim`] keymap in [`Mode::Normal`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current [`Mode`] of the [`Vim`] keymap.
+    pub fn mode(&self) -> Mode {
+        self.state.borrow().mode
+    }
+}
+
+impl<Message> Stateful<Message> for Vim
+where
+    Message: Clone,
+{
+    fn key_press(&self, key_press: KeyPress) -> Option<Binding<Message>> {
+        if key_press.status != Status::Focused {
+            return None;
+        }
+
+        let mut state = self.state.borrow_mut();
+
+        match state.mode {
+            Mode::Insert => {
+                if let keyboard::Key::Named(key::Named::Escape) = key_press.key {
+                    state.mode = Mode::Normal;
+
+                    Some(Binding::MoveLeftInLine)
+                } else {
+                    Binding::from_key_press(key_press)
+                }
+            }
+            Mode::Normal | Mode::Visual if key_press.modifiers.command() => {
+                Binding::from_key_press(key_press)
+            }
+            Mode::Normal => state.normal(&key_press),
+            Mode::Visual => state.visual(&key_press),
+        }
+    }
+
+    fn caret_shape(&self) -> Option<CaretShape> {
+        Some(match self.mode() {
+            Mode::Insert => CaretShape::Bar,
+            Mode::Normal | Mode::Visual => CaretShape::Block,
+        })
+    }
+}
+
+impl VimState {
+    fn normal<Message: Clone>(&mut self, key_press: &KeyPress) -> Option<Binding<Message>> {
+        let key = Key::from(key_press);
+
+        if let Key::Escape = key {
+            let is_pending = self.count.is_some() || self.operator.is_some() || self.is_g_pending;
+            self.reset();
+
+            return (!is_pending).then_some(Binding::Unfocus);
+        }
+
+        if let Some(binding) = self.prefix(&key) {
+            return Some(binding);
+        }
+
+        let count = self.count.take().unwrap_or(1);
+
+        if let Some(operator) = self.operator.take() {
+            let is_linewise = key == Key::Char(operator.key());
+
+            let selection = if is_linewise {
+                // Doubled operators apply to whole lines
+                Binding::SelectLines(count)
+            } else {
+                let motion = self.motion(&key)?;
+
+                repeat(Binding::Select(motion), count)
+            };
+
+            return Some(match operator {
+                Operator::Delete if is_linewise => {
+                    // Remove the line break of the deleted lines, too
+                    Binding::Sequence(vec![selection, Binding::Cut, Binding::Delete])
+                }
+                Operator::Delete => Binding::Sequence(vec![selection, Binding::Cut]),
+                Operator::Change => {
+                    self.mode = Mode::Insert;
+
+                    Binding::Sequence(vec![selection, Binding::Cut])
+                }
+                Operator::Yank => {
+                    Binding::Sequence(vec![selection, Binding::Copy, Binding::Move(Motion::Left)])
+                }
+            });
+        }
+
+        if let Some(motion) = self.motion(&key) {
+            return Some(repeat(Binding::Move(motion), count));
+        }
+
+        let Key::Char(c) = key else {
+            return None;
+        };
+
+        let binding = match c {
+            'd' | 'c' | 'y' => {
+                self.operator = Some(match c {
+                    'd' => Operator::Delete,
+                    'c' => Operator::Change,
+                    _ => Operator::Yank,
+                });
+                self.count = (count > 1).then_some(count);
+
+                Binding::Sequence(Vec::new())
+            }
+            'i' => self.insert(Vec::new()),
+            'a' => self.insert(vec![Binding::Move(Motion::Right)]),
+            'I' => self.insert(vec![Binding::Move(Motion::Home)]),
+            'A' => self.insert(vec![Binding::Move(Motion::End)]),
+            'o' => self.insert(vec![Binding::Move(Motion::End), Binding::Enter]),
+            'O' => self.insert(vec![
+                Binding::Move(Motion::Home),
+                Binding::Enter,
+                Binding::Move(Motion::Up),
+            ]),
+            'x' => repeat(Binding::Delete, count),
+            'X' => repeat(Binding::Backspace, count),
+            'D' => Binding::DeleteToLineEnd,
+            'C' => self.insert(vec![Binding::DeleteToLineEnd]),
+            'J' => repeat(Binding::JoinLines, count),
+            'p' => repeat(Binding::Paste, count),
+            'v' => {
+                self.mode = Mode::Visual;
+
+                Binding::Select(Motion::Right)
+            }
+            'V' => {
+                self.mode = Mode::Visual;
+
+                Binding::SelectLine
+            }
+            _ => return None,
+        };
+
+        Some(binding)
+    }
+
+    fn visual<Message: Clone>(&mut self, key_press: &KeyPress) -> Option<Binding<Message>> {
+        let key = Key::from(key_press);
+
+        if let Some(binding) = self.prefix(&key) {
+            return Some(binding);
+        }
+
+        let count = self.count.take().unwrap_or(1);
+
+        if let Some(motion) = self.motion(&key) {
+            return Some(repeat(Binding::Select(motion), count));
+        }
+
+        let binding = match key {
+            Key::Escape | Key::Char('v' | 'V') => {
+                self.reset();
+
+                Binding::Move(Motion::Left)
+            }
+            Key::Char('d' | 'x') => {
+                self.reset();
+
+                Binding::Cut
+            }
+            Key::Char('c') => {
+                self.reset();
+                self.mode = Mode::Insert;
+
+                Binding::Cut
+            }
+            Key::Char('y') => {
+                self.reset();
+
+                Binding::Sequence(vec![Binding::Copy, Binding::Move(Motion::Left)])
+            }
+            _ => return None,
+        };
+
+        Some(binding)
+    }
+
+    /// Keeps track of the given [`Key`] if it is the prefix of some command,
+    /// like a digit of a count or the first `g` of `gg`.
+    fn prefix<Message>(&mut self, key: &Key) -> Option<Binding<Message>> {
+        if let Some(digit) = key.digit(self.count.is_some()) {
+            let count = self.count.unwrap_or(0).saturating_mul(10);
+
+            self.count = Some(count.saturating_add(digit).min(MAX_COUNT));
+        } else if *key == Key::Char('g') && !self.is_g_pending {
+            self.is_g_pending = true;
+        } else {
+            return None;
+        }
+
+        Some(Binding::Sequence(Vec::new()))
+    }
+
+    /// Returns the [`Motion`] for the given [`Key`], completing a pending `gg`.
+    fn motion(&mut self, key: &Key) -> Option<Motion> {
+        if std::mem::take(&mut self.is_g_pending) {
+            return (*key == Key::Char('g')).then_some(Motion::DocumentStart);
+        }
+
+        match key {
+            Key::Char('h') | Key::Arrow(Motion::Left) => Some(Motion::Left),
+            Key::Char('l') | Key::Arrow(Motion::Right) => Some(Motion::Right),
+            Key::Char('k') | Key::Arrow(Motion::Up) => Some(Motion::Up),
+            Key::Char('j') | Key::Arrow(Motion::Down) => Some(Motion::Down),
+            Key::Char('b') => Some(Motion::WordLeft),
+            Key::Char('w' | 'e') => Some(Motion::WordRight),
+            Key::Char('0') => Some(Motion::Home),
+            Key::Char('$') => Some(Motion::End),
+            Key::Char('G') => Some(Motion::DocumentEnd),
+            Key::Arrow(motion) => Some(*motion),
+            _ => None,
+        }
+    }
+
+    fn insert<Message>(&mut self, mut bindings: Vec<Binding<Message>>) -> Binding<Message> {
+        self.mode = Mode::Insert;
+
+        if bindings.len() == 1 {
+            bindings.remove(0)
+        } else {
+            Binding::Sequence(bindings)
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// An Emacs-style keymap, with a mark and a kill ring.
+///
+/// Motions extend the selection while the mark is set with `C-space`, and
+/// killed text can be yanked back with `C-y` and cycled through with `M-y`.
+#[derive(Debug, Default)]
+pub struct Emacs {
+    state: RefCell<EmacsState>,
+}
+
+#[derive(Debug, Default)]
+struct EmacsState {
+    is_mark_active: bool,
+    /// Whether the point was last moved before the mark.
+    is_point_before_mark: bool,
+}
+
+impl Emacs {
+    /// Creates a new [`Emacs`] keymap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the mark is set, so motions extend the selection.
+    pub fn is_mark_active(&self) -> bool {
+        self.state.borrow().is_mark_active
+    }
+}
+
+impl<Message> Stateful<Message> for Emacs {
+    fn key_press(&self, key_press: KeyPress) -> Option<Binding<Message>> {
+        if key_press.status != Status::Focused {
+            return None;
+        }
+
+        let mut state = self.state.borrow_mut();
+
+        let modifiers = key_press.modifiers;
+        let control = modifiers.control() && !modifiers.alt();
+        let meta = modifiers.alt() && !modifiers.control();
+
+        let motion = match key_press.key.as_ref() {
+            keyboard::Key::Character(c) if control => match c {
+                "a" => Some(Motion::Home),
+                "e" => Some(Motion::End),
+                "f" => Some(Motion::Right),
+                "b" => Some(Motion::Left),
+                "n" => Some(Motion::Down),
+                "p" => Some(Motion::Up),
+                _ => None,
+            },
+            keyboard::Key::Character(c) if meta => match c {
+                "f" => Some(Motion::WordRight),
+                "b" => Some(Motion::WordLeft),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(motion) = motion {
+            return Some(state.motion(motion));
+        }
+
+        let binding = match key_press.key.as_ref() {
+            keyboard::Key::Named(key::Named::Space) if control => {
+                state.is_mark_active = true;
+                state.is_point_before_mark = false;
+
+                Binding::Sequence(Vec::new())
+            }
+            keyboard::Key::Character("g") if control => {
+                if state.is_mark_active {
+                    state.deactivate_mark()
+                } else {
+                    Binding::Sequence(Vec::new())
+                }
+            }
+            keyboard::Key::Character("d") if control => Binding::Delete,
+            keyboard::Key::Character("k") if control => {
+                state.is_mark_active = false;
+
+                Binding::KillToLineEnd
+            }
+            keyboard::Key::Character("w") if control => {
+                state.is_mark_active = false;
+
+                Binding::KillSelection
+            }
+            keyboard::Key::Character("w") if meta => {
+                let mut bindings = vec![Binding::CopyToKillRing];
+
+                if state.is_mark_active {
+                    bindings.push(state.deactivate_mark());
+                }
+
+                Binding::Sequence(bindings)
+            }
+            keyboard::Key::Character("y") if control => {
+                state.is_mark_active = false;
+
+                Binding::Yank
+            }
+            keyboard::Key::Character("y") if meta => Binding::YankPop,
+            _ => match Binding::from_key_press(key_press)? {
+                // Arrow keys extend the selection while the mark is active, too
+                Binding::Move(motion) => state.motion(motion),
+                binding => {
+                    state.is_mark_active = false;
+
+                    binding
+                }
+            },
+        };
+
+        Some(binding)
+    }
+}
+
+impl EmacsState {
+    fn motion<Message>(&mut self, motion: Motion) -> Binding<Message> {
+        if self.is_mark_active {
+            self.is_point_before_mark = motion.direction() == Direction::Left;
+
+            Binding::Select(motion)
+        } else {
+            Binding::Move(motion)
+        }
+    }
+
+    /// Deactivates the mark, collapsing the selection to the point.
+    fn deactivate_mark<Message>(&mut self) -> Binding<Message> {
+        self.is_mark_active = false;
+
+        Binding::Move(if self.is_point_before_mark {
+            Motion::Left
+        } else {
+            Motion::Right
+        })
+    }
+}
+
+impl Operator {
+    fn key(self) -> char {
+        match self {
+            Self::Delete => 'd',
+            Self::Change => 'c',
+            Self::Yank => 'y',
+        }
+    }
+}
+
+/// A key press of a modal keymap, in normal or visual mode.
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+    Char(char),
+    Arrow(Motion),
+    Escape,
+    Other,
+}
+
+impl Key {
+    /// Returns the digit of the [`Key`], if it is part of a count.
+    ///
+    /// A `0` only continues a count, since it moves to the start of the line
+    /// otherwise.
+    fn digit(&self, is_counting: bool) -> Option<usize> {
+        let Self::Char(c) = self else {
+            return None;
+        };
+
+        let digit = c.to_digit(10)? as usize;
+
+        (digit > 0 || is_counting).then_some(digit)
+    }
+}
+
+impl From<&KeyPress> for Key {
+    fn from(key_press: &KeyPress) -> Self {
+        match key_press.key.as_ref() {
+            keyboard::Key::Named(key::Named::Escape) => Self::Escape,
+            keyboard::Key::Named(key::Named::ArrowLeft | key::Named::Backspace) => {
+                Self::Arrow(Motion::Left)
+            }
+            keyboard::Key::Named(key::Named::ArrowRight | key::Named::Space) => {
+                Self::Arrow(Motion::Right)
+            }
+            keyboard::Key::Named(key::Named::ArrowUp) => Self::Arrow(Motion::Up),
+            keyboard::Key::Named(key::Named::ArrowDown | key::Named::Enter) => {
+                Self::Arrow(Motion::Down)
+            }
+            keyboard::Key::Named(key::Named::Home) => Self::Arrow(Motion::Home),
+            keyboard::Key::Named(key::Named::End) => Self::Arrow(Motion::End),
+            keyboard::Key::Named(key::Named::PageUp) => Self::Arrow(Motion::PageUp),
+            keyboard::Key::Named(key::Named::PageDown) => Self::Arrow(Motion::PageDown),
+            _ => key_press
+                .text
+                .as_deref()
+                .and_then(|text| text.chars().next())
+                .filter(|c| !c.is_control())
+                .map_or(Self::Other, Self::Char),
+        }
+    }
+}
+
+/// Returns a [`Binding`] that applies the given one `count` times, up to
+/// [`MAX_COUNT`].
+fn repeat<Message: Clone>(binding: Binding<Message>, count: usize) -> Binding<Message> {
+    if count > 1 {
+        Binding::Sequence(vec![binding; count.min(MAX_COUNT)])
+    } else {
+        binding
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(c: char) -> KeyPress {
+        KeyPress {
+            key: keyboard::Key::Character(c.to_string().into()),
+            modifiers: keyboard::Modifiers::default(),
+            text: Some(c.to_string().into()),
+            status: Status::Focused,
+        }
+    }
+
+    fn control(c: char) -> KeyPress {
+        KeyPress {
+            modifiers: keyboard::Modifiers::CTRL,
+            ..press(c)
+        }
+    }
+
+    fn meta(c: char) -> KeyPress {
+        KeyPress {
+            modifiers: keyboard::Modifiers::ALT,
+            ..press(c)
+        }
+    }
+
+    fn type_keys(vim: &Vim, keys: &str) -> Option<Binding<()>> {
+        keys.chars()
+            .map(|c| vim.key_press(press(c)))
+            .last()
+            .flatten()
+    }
+
+    #[test]
+    fn counts_repeat_motions() {
+        let vim = Vim::new();
+
+        assert_eq!(
+            type_keys(&vim, "3l"),
+            Some(Binding::Sequence(vec![Binding::Move(Motion::Right); 3]))
+        );
+        assert_eq!(
+            type_keys(&vim, "10x"),
+            Some(Binding::Sequence(vec![Binding::Delete; 10]))
+        );
+    }
+
+    #[test]
+    fn zero_only_continues_a_count() {
+        let vim = Vim::new();
+
+        assert_eq!(type_keys(&vim, "0"), Some(Binding::Move(Motion::Home)));
+    }
+
+    #[test]
+    fn long_counts_are_clamped() {
+        let vim = Vim::new();
+
+        let _ = type_keys(&vim, "999999999999999999999999");
+
+        assert_eq!(vim.state.borrow().count, Some(MAX_COUNT));
+
+        let Some(Binding::Sequence(bindings)) = type_keys(&vim, "j") else {
+            panic!("a count must repeat the motion");
+        };
+
+        assert_eq!(bindings.len(), MAX_COUNT);
+    }
+
+    #[test]
+    fn repeat() {
+        assert_eq!(super::repeat(Binding::<()>::Paste, 1), Binding::Paste);
+        assert_eq!(
+            super::repeat(Binding::<()>::Paste, 2),
+            Binding::Sequence(vec![Binding::Paste, Binding::Paste])
+        );
+
+        let Binding::Sequence(bindings) = super::repeat(Binding::<()>::Paste, usize::MAX) else {
+            panic!("a count must produce a sequence");
+        };
+
+        assert_eq!(bindings.len(), MAX_COUNT);
+    }
+
+    #[test]
+    fn emacs_motions() {
+        let emacs = Emacs::new();
+
+        assert_eq!(
+            emacs.key_press(control('f')),
+            Some(Binding::<()>::Move(Motion::Right))
+        );
+        assert_eq!(
+            emacs.key_press(control('a')),
+            Some(Binding::<()>::Move(Motion::Home))
+        );
+        assert_eq!(
+            emacs.key_press(meta('b')),
+            Some(Binding::<()>::Move(Motion::WordLeft))
+        );
+    }
+
+    #[test]
+    fn emacs_mark() {
+        let emacs = Emacs::new();
+
+        let space = KeyPress {
+            key: keyboard::Key::Named(key::Named::Space),
+            modifiers: keyboard::Modifiers::CTRL,
+            text: None,
+            status: Status::Focused,
+        };
+
+        let _ = Stateful::<()>::key_press(&emacs, space);
+
+        assert!(emacs.is_mark_active());
+        assert_eq!(
+            emacs.key_press(control('b')),
+            Some(Binding::<()>::Select(Motion::Left))
+        );
+
+        // Quitting collapses the selection to the point
+        assert_eq!(
+            emacs.key_press(control('g')),
+            Some(Binding::<()>::Move(Motion::Left))
+        );
+        assert!(!emacs.is_mark_active());
+        assert_eq!(
+            emacs.key_press(control('b')),
+            Some(Binding::<()>::Move(Motion::Left))
+        );
+    }
+
+    #[test]
+    fn emacs_kill_ring() {
+        let emacs = Emacs::new();
+
+        assert_eq!(
+            emacs.key_press(control('k')),
+            Some(Binding::<()>::KillToLineEnd)
+        );
+        assert_eq!(
+            emacs.key_press(control('w')),
+            Some(Binding::<()>::KillSelection)
+        );
+        assert_eq!(
+            emacs.key_press(meta('w')),
+            Some(Binding::<()>::Sequence(vec![Binding::CopyToKillRing]))
+        );
+        assert_eq!(emacs.key_press(control('y')), Some(Binding::<()>::Yank));
+        assert_eq!(emacs.key_press(meta('y')), Some(Binding::<()>::YankPop));
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::KeyPress;
+use crate::core::keyboard::{self, key};
+
+/// The named keys of a [`Shortcut`], along with their names.
+const NAMED_KEYS: &[(&str, key::Named)] = &[
+    ("enter", key::Named::Enter),
+    ("tab", key::Named::Tab),
+    ("space", key::Named::Space),
+    ("backspace", key::Named::Backspace),
+    ("delete", key::Named::Delete),
+    ("insert", key::Named::Insert),
+    ("escape", key::Named::Escape),
+    ("left", key::Named::ArrowLeft),
+    ("right", key::Named::ArrowRight),
+    ("up", key::Named::ArrowUp),
+    ("down", key::Named::ArrowDown),
+    ("home", key::Named::Home),
+    ("end", key::Named::End),
+    ("pageup", key::Named::PageUp),
+    ("pagedown", key::Named::PageDown),
+    ("f1", key::Named::F1),
+    ("f2", key::Named::F2),
+    ("f3", key::Named::F3),
+    ("f4", key::Named::F4),
+    ("f5", key::Named::F5),
+    ("f6", key::Named::F6),
+    ("f7", key::Named::F7),
+    ("f8", key::Named::F8),
+    ("f9", key::Named::F9),
+    ("f10", key::Named::F10),
+    ("f11", key::Named::F11),
+    ("f12", key::Named::F12),
+];
+
+/// A key combined with some modifiers, like `cmd+shift+z`.
+///
+/// The `cmd` modifier stands for the platform command key: the logo key on
+/// macOS, and control elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shortcut {
+    command: bool,
+    control: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+    key: String,
+}
+
+impl Shortcut {
+    /// Returns the [`Shortcut`] of the given [`KeyPress`], if its key has a name.
+    pub fn from_key_press(key_press: &KeyPress) -> Option<Self> {
+        let modifiers = key_press.modifiers;
+
+        Some(Self {
+            command: false,
+            control: modifiers.control(),
+            alt: modifiers.alt(),
+            shift: modifiers.shift(),
+            logo: modifiers.logo(),
+            key: key_name(&key_press.key)?,
+        })
+    }
+
+    /// Returns true if the given [`KeyPress`] triggers the [`Shortcut`].
+    pub fn matches(&self, key_press: &KeyPress) -> bool {
+        let modifiers = key_press.modifiers;
+
+        let (control, logo) = if !self.command {
+            (self.control, self.logo)
+        } else if cfg!(target_os = "macos") {
+            (self.control, true)
+        } else {
+            (true, self.logo)
+        };
+
+        modifiers.control() == control
+            && modifiers.alt() == self.alt
+            && modifiers.shift() == self.shift
+            && modifiers.logo() == logo
+            && key_name(&key_press.key).is_some_and(|name| name == self.key)
+    }
+}
+
+/// Returns the name of the given key in a [`Shortcut`].
+fn key_name(key: &keyboard::Key) -> Option<String> {
+    match key.as_ref() {
+        keyboard::Key::Character(c) => Some(c.to_lowercase()),
+        keyboard::Key::Named(named) => NAMED_KEYS
+            .iter()
+            .find(|(_, key)| *key == named)
+            .map(|(name, _)| (*name).to_owned()),
+        keyboard::Key::Unidentified => None,
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+
+        // The plus key itself is written as a trailing `+`
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None if s == "+" => ("", "+"),
+            None => s.rsplit_once('+').unwrap_or(("", &s)),
+        };
+
+        let key = match key {
+            "esc" => "escape",
+            "return" => "enter",
+            "del" => "delete",
+            "page-up" => "pageup",
+            "page-down" => "pagedown",
+            key => key,
+        };
+
+        if key.chars().count() != 1 && !NAMED_KEYS.iter().any(|(name, _)| *name == key) {
+            return Err(ParseError::UnknownKey(key.to_owned()));
+        }
+
+        let mut shortcut = Self {
+            command: false,
+            control: false,
+            alt: false,
+            shift: false,
+            logo: false,
+            key: key.to_owned(),
+        };
+
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            let flag = match modifier.trim() {
+                "cmd" | "command" => &mut shortcut.command,
+                "ctrl" | "control" => &mut shortcut.control,
+                "alt" | "option" | "opt" => &mut shortcut.alt,
+                "shift" => &mut shortcut.shift,
+                "logo" | "super" | "meta" | "win" => &mut shortcut.logo,
+                modifier => return Err(ParseError::UnknownModifier(modifier.to_owned())),
+            };
+
+            *flag = true;
+        }
+
+        Ok(shortcut)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.command, "cmd"),
+            (self.control, "ctrl"),
+            (self.alt, "alt"),
+            (self.shift, "shift"),
+            (self.logo, "logo"),
+        ];
+
+        for (_, name) in modifiers.iter().filter(|(is_pressed, _)| *is_pressed) {
+            write!(f, "{name}+")?;
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Shortcut {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Shortcut {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A sequence of [`Shortcut`]s pressed one after the other, like
+/// `ctrl+k ctrl+c`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Chord(Vec<Shortcut>);
+
+impl Chord {
+    /// Returns the [`Shortcut`]s of the [`Chord`], in order.
+    pub fn shortcuts(&self) -> &[Shortcut] {
+        &self.0
+    }
+
+    /// Returns whether the given key presses complete the [`Chord`], or
+    /// `None` if they do not even start it.
+    pub fn matches(&self, key_presses: &[KeyPress]) -> Option<bool> {
+        let is_prefix = key_presses.len() <= self.0.len()
+            && self
+                .0
+                .iter()
+                .zip(key_presses)
+                .all(|(shortcut, key_press)| shortcut.matches(key_press));
+
+        is_prefix.then_some(key_presses.len() == self.0.len())
+    }
+}
+
+impl From<Shortcut> for Chord {
+    fn from(shortcut: Shortcut) -> Self {
+        Self(vec![shortcut])
+    }
+}
+
+impl FromStr for Chord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let shortcuts = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if shortcuts.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        Ok(Self(shortcuts))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, shortcut) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{shortcut}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Chord {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Chord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// An error produced when parsing a [`Shortcut`] or a [`Chord`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// There are no keys at all.
+    Empty,
+    /// The modifier is not known.
+    UnknownModifier(String),
+    /// The key is not known.
+    UnknownKey(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no keys"),
+            Self::UnknownModifier(modifier) => write!(f, "unknown modifier `{modifier}`"),
+            Self::UnknownKey(key) => write!(f, "unknown key `{key}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T>(text: &str) -> String
+    where
+        T: FromStr<Err = ParseError> + fmt::Display,
+    {
+        let parsed: T = text.parse().unwrap();
+        let displayed = parsed.to_string();
+
+        assert_eq!(displayed.parse::<T>().unwrap().to_string(), displayed);
+
+        displayed
+    }
+
+    #[test]
+    fn shortcut_round_trip() {
+        assert_eq!(round_trip::<Shortcut>("cmd+shift+z"), "cmd+shift+z");
+        assert_eq!(round_trip::<Shortcut>("Shift+Ctrl+Left"), "ctrl+shift+left");
+        assert_eq!(round_trip::<Shortcut>("super+alt+f4"), "alt+logo+f4");
+        assert_eq!(round_trip::<Shortcut>("a"), "a");
+    }
+
+    #[test]
+    fn shortcut_aliases() {
+        assert_eq!(round_trip::<Shortcut>("esc"), "escape");
+        assert_eq!(round_trip::<Shortcut>("option+return"), "alt+enter");
+        assert_eq!(round_trip::<Shortcut>("control+page-down"), "ctrl+pagedown");
+    }
+
+    #[test]
+    fn plus_key() {
+        assert_eq!(round_trip::<Shortcut>("+"), "+");
+        assert_eq!(round_trip::<Shortcut>("ctrl++"), "ctrl++");
+    }
+
+    #[test]
+    fn shortcut_errors() {
+        assert_eq!(
+            "hyper+a".parse::<Shortcut>(),
+            Err(ParseError::UnknownModifier(String::from("hyper")))
+        );
+        assert_eq!(
+            "ctrl+nope".parse::<Shortcut>(),
+            Err(ParseError::UnknownKey(String::from("nope")))
+        );
+    }
+
+    #[test]
+    fn chord_round_trip() {
+        assert_eq!(round_trip::<Chord>("ctrl+k  ctrl+c"), "ctrl+k ctrl+c");
+        assert_eq!(round_trip::<Chord>("g g"), "g g");
+
+        let chord: Chord = "ctrl+k ctrl+c".parse().unwrap();
+
+        assert_eq!(chord.shortcuts().len(), 2);
+    }
+
+    #[test]
+    fn chord_errors() {
+        assert_eq!("  ".parse::<Chord>(), Err(ParseError::Empty));
+        assert_eq!(
+            "ctrl+k meta+".parse::<Chord>(),
+            Err(ParseError::UnknownKey(String::new()))
+        );
+    }
+}
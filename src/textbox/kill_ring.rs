@@ -0,0 +1,208 @@
+//! Keep the text cut by kill bindings around, so it can be yanked back.
+use std::collections::VecDeque;
+
+use super::{Binding, Content};
+use crate::core::text;
+
+/// The maximum amount of entries of a [`KillRing`].
+const CAPACITY: usize = 60;
+
+/// The text killed in a [`TextBox`], from the most to the least recent.
+///
+/// [`TextBox`]: super::TextBox
+#[derive(Debug, Default)]
+pub(super) struct KillRing {
+    entries: VecDeque<String>,
+    /// Whether the last binding was a kill, so the next one is appended to it.
+    is_appending: bool,
+    /// The last entry yanked, if the yank can still be cycled.
+    yanked: Option<Yank>,
+}
+
+/// An entry of a [`KillRing`] inserted in a [`Content`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Yank {
+    /// The index of the entry in the [`KillRing`].
+    pub(super) index: usize,
+    /// The line and byte column where the inserted text starts.
+    pub(super) start: (usize, usize),
+    /// The line and byte column where the inserted text ends.
+    pub(super) end: (usize, usize),
+}
+
+impl KillRing {
+    /// Adds the given text to the [`KillRing`], returning the whole entry it
+    /// ended up in.
+    pub(super) fn kill(&mut self, text: &str) -> &str {
+        if self.is_appending
+            && let Some(entry) = self.entries.front_mut()
+        {
+            entry.push_str(text);
+        } else {
+            self.entries.push_front(text.to_owned());
+            self.entries.truncate(CAPACITY);
+        }
+
+        self.is_appending = true;
+
+        self.entries.front().map_or("", String::as_str)
+    }
+
+    /// Returns the entry of the [`KillRing`] at the given index, counting
+    /// from the most recent one.
+    pub(super) fn entry(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Notes the [`Yank`] of an entry, so it can be replaced by the previous
+    /// one.
+    pub(super) fn yanked(&mut self, yank: Yank) {
+        self.yanked = Some(yank);
+    }
+
+    /// Returns the last [`Yank`], along with the index of the entry preceding
+    /// it, which must replace it.
+    pub(super) fn yank_pop(&self) -> Option<(Yank, usize)> {
+        let yank = self.yanked?;
+
+        Some((yank, (yank.index + 1) % self.entries.len()))
+    }
+
+    /// Stops appending kills and cycling yanks, unless the given [`Binding`]
+    /// continues them.
+    pub(super) fn interrupt<Message>(&mut self, binding: &Binding<Message>) {
+        if !matches!(
+            binding,
+            Binding::KillToLineEnd | Binding::KillSelection | Binding::CopyToKillRing
+        ) {
+            self.is_appending = false;
+        }
+
+        if !matches!(binding, Binding::Yank | Binding::YankPop) {
+            self.yanked = None;
+        }
+    }
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Returns true if the caret is still right after the text inserted by
+    /// the [`Yank`], with nothing selected.
+    pub(super) fn is_after(&self, yank: &Yank) -> bool {
+        self.selection().is_none() && self.cursor_position() == yank.end && yank.start <= yank.end
+    }
+
+    /// Returns the text between the given lines and byte columns, with the
+    /// line breaks between them.
+    pub(super) fn text_between(&self, from: (usize, usize), to: (usize, usize)) -> Option<String> {
+        let mut text = String::new();
+
+        for index in from.0..=to.0 {
+            let line = self.line(index)?;
+
+            let start = if index == from.0 { from.1 } else { 0 };
+            let end = if index == to.0 { to.1 } else { line.text.len() };
+
+            text.push_str(line.text.get(start..end)?);
+
+            if index < to.0 {
+                text.push_str(line.ending.as_str());
+            }
+        }
+
+        Some(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textbox::{Action, Edit, Motion};
+
+    fn kill_ring(entries: &[&str]) -> KillRing {
+        let mut kill_ring = KillRing::default();
+
+        for entry in entries.iter().rev() {
+            kill_ring.interrupt(&Binding::<()>::Delete);
+            kill_ring.kill(entry);
+        }
+
+        kill_ring
+    }
+
+    fn yank(index: usize) -> Yank {
+        Yank {
+            index,
+            start: (0, 0),
+            end: (0, 1),
+        }
+    }
+
+    #[test]
+    fn consecutive_kills_are_appended() {
+        let mut kill_ring = KillRing::default();
+
+        kill_ring.kill("one");
+        kill_ring.interrupt(&Binding::<()>::KillToLineEnd);
+
+        assert_eq!(kill_ring.kill(" two"), "one two");
+
+        kill_ring.interrupt(&Binding::<()>::Move(Motion::Right));
+        kill_ring.kill("three");
+
+        assert_eq!(kill_ring.entry(0), Some("three"));
+        assert_eq!(kill_ring.entry(1), Some("one two"));
+    }
+
+    #[test]
+    fn yank_pop_rotates_the_entries() {
+        let mut kill_ring = kill_ring(&["a", "b", "c"]);
+
+        assert_eq!(kill_ring.yank_pop(), None);
+
+        kill_ring.yanked(yank(0));
+
+        assert_eq!(kill_ring.yank_pop(), Some((yank(0), 1)));
+
+        kill_ring.interrupt(&Binding::<()>::YankPop);
+        kill_ring.yanked(yank(2));
+
+        assert_eq!(kill_ring.yank_pop(), Some((yank(2), 0)));
+
+        kill_ring.interrupt(&Binding::<()>::Insert('x'));
+
+        assert_eq!(kill_ring.yank_pop(), None);
+    }
+
+    #[test]
+    fn capacity() {
+        let kill_ring = kill_ring(&["entry"; CAPACITY + 1]);
+
+        assert_eq!(kill_ring.entries.len(), CAPACITY);
+    }
+
+    #[test]
+    fn yanked_text() {
+        let mut content = Content::<iced::Renderer>::with_text("one\ntwo");
+
+        content.perform(Action::Move(Motion::DocumentEnd));
+
+        let yank = Yank {
+            index: 0,
+            start: (0, 1),
+            end: (1, 3),
+        };
+
+        assert!(content.is_after(&yank));
+        assert_eq!(
+            content.text_between(yank.start, yank.end).as_deref(),
+            Some("ne\ntwo")
+        );
+
+        content.perform(Action::Edit(Edit::Backspace));
+
+        assert!(!content.is_after(&yank));
+    }
+}
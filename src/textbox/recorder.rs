@@ -0,0 +1,99 @@
+//! Record the bindings applied to a text box, so they can be replayed.
+use std::convert::Infallible;
+
+use super::Binding;
+
+/// The bindings recorded in a [`TextBox`], as a macro.
+///
+/// Only built-in bindings are recorded, since custom ones carry messages of
+/// the application.
+///
+/// [`TextBox`]: super::TextBox
+#[derive(Debug, Default)]
+pub(super) struct Recorder {
+    recording: Option<Vec<Binding<Infallible>>>,
+    recorded: Vec<Binding<Infallible>>,
+}
+
+impl Recorder {
+    /// Returns true if the [`Recorder`] is recording bindings.
+    pub(super) fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Starts recording a new macro, discarding any unfinished one.
+    pub(super) fn start(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// Stops recording, keeping the recorded bindings as the macro to replay.
+    pub(super) fn stop(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            self.recorded = recording;
+        }
+    }
+
+    /// Records the given [`Binding`], if recording.
+    ///
+    /// The bindings controlling the recording itself are never recorded, and
+    /// neither are the ones that do nothing, like the empty sequences of the
+    /// prefix keys of a modal keymap.
+    pub(super) fn record<Message>(&mut self, binding: &Binding<Message>) {
+        if matches!(
+            binding,
+            Binding::StartRecording | Binding::StopRecording | Binding::Replay(_)
+        ) {
+            return;
+        }
+
+        if let Some(recording) = &mut self.recording
+            && let Some(binding) = binding.to_builtin()
+            && !is_empty(&binding)
+        {
+            recording.push(binding);
+        }
+    }
+
+    /// Returns the bindings of the last recorded macro.
+    pub(super) fn recorded<Message>(&self) -> Vec<Binding<Message>> {
+        self.recorded
+            .iter()
+            .filter_map(Binding::to_builtin)
+            .collect()
+    }
+}
+
+/// Returns true if the [`Binding`] is a sequence without any binding, even
+/// nested.
+fn is_empty(binding: &Binding<Infallible>) -> bool {
+    matches!(binding, Binding::Sequence(sequence) if sequence.iter().all(is_empty))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_only_what_does_something() {
+        let mut recorder = Recorder::default();
+
+        recorder.record(&Binding::<()>::Insert('a'));
+        recorder.start();
+        recorder.record(&Binding::<()>::Insert('b'));
+        recorder.record(&Binding::Custom(()));
+        recorder.record(&Binding::<()>::Sequence(vec![Binding::Sequence(vec![])]));
+        recorder.record(&Binding::<()>::Replay(1));
+        recorder.record(&Binding::<()>::StartRecording);
+        recorder.record(&Binding::<()>::Backspace);
+
+        assert!(recorder.recorded::<()>().is_empty());
+
+        recorder.stop();
+
+        assert!(!recorder.is_recording());
+        assert_eq!(
+            recorder.recorded::<()>(),
+            vec![Binding::Insert('b'), Binding::Backspace]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Mask the text of a secure text box, like a password field.
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::Cursor;
+use crate::core::{Point, Rectangle, Size};
+
+/// The character shown in place of each grapheme of a secure [`TextBox`].
+///
+/// [`TextBox`]: super::TextBox
+pub(super) const MASK: char = '•';
+
+/// Returns the given lines with each of their graphemes masked.
+pub(super) fn mask(lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|line| MASK.to_string().repeat(graphemes(line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the [`Cursor`] of the masked text, given the lines of the actual
+/// text, the line and byte column of its caret and the ones of the start and
+/// the end of its selection, if any.
+///
+/// Every grapheme of the masked text has the same width, and lines never
+/// wrap.
+pub(super) fn cursor(
+    lines: &[String],
+    caret: (usize, usize),
+    selection: Option<((usize, usize), (usize, usize))>,
+    mask_width: f32,
+    line_height: f32,
+) -> Cursor {
+    let column = |(line, column): (usize, usize)| {
+        lines
+            .get(line)
+            .and_then(|text| text.get(..column))
+            .map_or(0, graphemes)
+    };
+
+    let point = |line: usize, index: usize| {
+        Point::new(index as f32 * mask_width, line as f32 * line_height)
+    };
+
+    let Some((start, end)) = selection else {
+        return Cursor::Caret(point(caret.0, column(caret)));
+    };
+
+    Cursor::Selection(
+        (start.0..=end.0)
+            .map(|line| {
+                let from = if line == start.0 { column(start) } else { 0 };
+                let to = if line == end.0 {
+                    column(end)
+                } else {
+                    lines.get(line).map_or(0, |text| graphemes(text))
+                };
+
+                Rectangle::new(
+                    point(line, from),
+                    Size::new(to.saturating_sub(from) as f32 * mask_width, line_height),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Returns the line and byte column of the grapheme boundary of the actual
+/// text closest to the given position of the masked text.
+pub(super) fn hit_test(
+    lines: &[String],
+    position: Point,
+    mask_width: f32,
+    line_height: f32,
+) -> (usize, usize) {
+    let line = ((position.y / line_height).max(0.0) as usize).min(lines.len().saturating_sub(1));
+
+    let column = lines.get(line).map_or(0, |text| {
+        let index = (position.x / mask_width).round().max(0.0) as usize;
+
+        text.grapheme_indices(true)
+            .nth(index)
+            .map_or(text.len(), |(column, _)| column)
+    });
+
+    (line, column)
+}
+
+fn graphemes(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: f32 = 10.0;
+    const HEIGHT: f32 = 20.0;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split('\n').map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn masks_every_grapheme() {
+        assert_eq!(mask(&lines("ab\ne\u{301}")), "••\n•");
+    }
+
+    #[test]
+    fn caret() {
+        let cursor = cursor(&lines("ab\ne\u{301}x"), (1, 3), None, WIDTH, HEIGHT);
+
+        assert!(matches!(cursor, Cursor::Caret(point) if point == Point::new(WIDTH, HEIGHT)));
+    }
+
+    #[test]
+    fn selection_across_lines() {
+        let cursor = cursor(
+            &lines("abc\ne\u{301}x"),
+            (1, 3),
+            Some(((0, 1), (1, 3))),
+            WIDTH,
+            HEIGHT,
+        );
+
+        let Cursor::Selection(ranges) = cursor else {
+            panic!("a selection must be drawn as ranges");
+        };
+
+        assert_eq!(
+            ranges,
+            vec![
+                Rectangle::new(Point::new(WIDTH, 0.0), Size::new(2.0 * WIDTH, HEIGHT)),
+                Rectangle::new(Point::new(0.0, HEIGHT), Size::new(WIDTH, HEIGHT)),
+            ]
+        );
+    }
+
+    #[test]
+    fn hit_test_snaps_to_graphemes() {
+        let lines = lines("ab\ne\u{301}x");
+
+        assert_eq!(
+            hit_test(&lines, Point::new(14.0, 5.0), WIDTH, HEIGHT),
+            (0, 1)
+        );
+        assert_eq!(
+            hit_test(&lines, Point::new(6.0, 25.0), WIDTH, HEIGHT),
+            (1, 3)
+        );
+        assert_eq!(
+            hit_test(&lines, Point::new(16.0, 25.0), WIDTH, HEIGHT),
+            (1, 4)
+        );
+    }
+
+    #[test]
+    fn hit_test_clamps_to_the_text() {
+        let lines = lines("ab\ncd");
+
+        assert_eq!(
+            hit_test(&lines, Point::new(-5.0, -5.0), WIDTH, HEIGHT),
+            (0, 0)
+        );
+        assert_eq!(
+            hit_test(&lines, Point::new(500.0, 500.0), WIDTH, HEIGHT),
+            (1, 2)
+        );
+    }
+}
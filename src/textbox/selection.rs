@@ -0,0 +1,132 @@
+//! Track where the selection of a [`Content`] is anchored, following the
+//! editor.
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Action, Content};
+use crate::core::text;
+
+/// The position a selection of a [`Content`] was started from, which stays
+/// put while the caret extends the selection.
+///
+/// Word and line selections extend by whole words and lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Anchor {
+    Char((usize, usize)),
+    Word((usize, usize)),
+    Line((usize, usize)),
+}
+
+impl Anchor {
+    /// Returns the [`Anchor`] after the given [`Action`], given the one before
+    /// it and the line and byte column of the caret before it.
+    ///
+    /// Selecting motions and drags keep the [`Anchor`] of an existing
+    /// selection, while any other motion or edit drops it.
+    pub(super) fn after(
+        anchor: Option<Self>,
+        action: &Action,
+        caret: (usize, usize),
+    ) -> Option<Self> {
+        match action {
+            Action::Select(_) | Action::Drag(_) => Some(anchor.unwrap_or(Self::Char(caret))),
+            Action::SelectWord => Some(Self::Word(caret)),
+            Action::SelectLine => Some(Self::Line(caret)),
+            Action::SelectAll => Some(Self::Char((0, 0))),
+            Action::Scroll { .. } => anchor,
+            Action::Move(_) | Action::Click(_) | Action::Edit(_) => None,
+        }
+    }
+
+    /// Returns the line and byte column of the [`Anchor`].
+    pub(super) fn position(self) -> (usize, usize) {
+        match self {
+            Self::Char(position) | Self::Word(position) | Self::Line(position) => position,
+        }
+    }
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Returns the line and byte column of the start and the end of the
+    /// selection of the [`Content`], if any.
+    pub(super) fn selection_bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.0.borrow().anchor?;
+        let caret = self.cursor_position();
+
+        let start = anchor.position().min(caret);
+        let end = anchor.position().max(caret);
+
+        let length = |line: usize| self.line(line).map_or(0, |line| line.text.len());
+
+        Some(match anchor {
+            Anchor::Char(_) => (start, end),
+            Anchor::Line(_) => ((start.0, 0), (end.0, length(end.0))),
+            Anchor::Word(_) => {
+                // The ends are moved to the boundaries of their words
+                let start_index = self.line(start.0).and_then(|line| {
+                    line.text
+                        .unicode_word_indices()
+                        .rev()
+                        .map(|(index, _)| index)
+                        .find(|&index| index <= start.1)
+                });
+
+                let end_index = self.line(end.0).and_then(|line| {
+                    line.text
+                        .unicode_word_indices()
+                        .map(|(index, word)| index + word.len())
+                        .find(|&index| index >= end.1)
+                });
+
+                (
+                    (start.0, start_index.unwrap_or(0)),
+                    (end.0, end_index.unwrap_or_else(|| length(end.0))),
+                )
+            }
+        })
+    }
+
+    /// Returns true if the caret is before the [`Anchor`] of the selection,
+    /// so it was extended backwards.
+    pub(super) fn is_selecting_backwards(&self) -> bool {
+        self.0
+            .borrow()
+            .anchor
+            .is_some_and(|anchor| self.cursor_position() < anchor.position())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textbox::Motion;
+
+    type Content = super::Content<iced::Renderer>;
+
+    fn content(text: &str, column: usize) -> Content {
+        let mut content = Content::with_text(text);
+
+        for _ in 0..column {
+            content.perform(Action::Move(Motion::Right));
+        }
+
+        content
+    }
+
+    #[test]
+    fn word_selections_keep_their_bounds() {
+        let mut content = content("one two three", 4);
+        content.perform(Action::SelectWord);
+        content.perform(Action::Select(Motion::Right));
+
+        assert_eq!(content.selection_bounds(), Some(((0, 4), (0, 7))));
+
+        let mut content = self::content("one two three", 7);
+        content.perform(Action::SelectWord);
+        content.perform(Action::Select(Motion::Left));
+
+        assert_eq!(content.selection_bounds(), Some(((0, 4), (0, 7))));
+    }
+}
@@ -0,0 +1,286 @@
+//! Count the characters, words, lines and paragraphs of a [`Content`].
+use std::time::Duration;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Change, Content};
+use crate::core::text;
+
+/// The statistics of the text of a [`Content`] and of its selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// The [`Counts`] of the whole text.
+    pub text: Counts,
+    /// The [`Counts`] of the selected text, if any.
+    pub selection: Option<Counts>,
+}
+
+/// The counts of some text. Line breaks are not counted as characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    /// The amount of characters.
+    pub chars: usize,
+    /// The amount of user-perceived characters, or grapheme clusters.
+    pub graphemes: usize,
+    /// The amount of words, split at Unicode word boundaries.
+    pub words: usize,
+    /// The amount of lines.
+    pub lines: usize,
+    /// The amount of paragraphs, which are separated by blank lines.
+    pub paragraphs: usize,
+}
+
+impl Counts {
+    /// Counts the given text.
+    pub fn of(text: &str) -> Self {
+        Self::from_lines(&text.split('\n').map(Line::of).collect::<Vec<_>>())
+    }
+
+    /// Returns the time it takes to read the words, at the given reading
+    /// speed.
+    pub fn reading_time(&self, words_per_minute: u32) -> Duration {
+        Duration::from_secs_f64(self.words as f64 * 60.0 / f64::from(words_per_minute.max(1)))
+    }
+
+    fn from_lines(lines: &[Line]) -> Self {
+        let mut counts = Self {
+            lines: lines.len(),
+            ..Self::default()
+        };
+
+        let mut is_previous_blank = true;
+
+        for line in lines {
+            counts.chars += line.chars;
+            counts.graphemes += line.graphemes;
+            counts.words += line.words;
+
+            if is_previous_blank && !line.is_blank {
+                counts.paragraphs += 1;
+            }
+
+            is_previous_blank = line.is_blank;
+        }
+
+        counts
+    }
+}
+
+/// The counts of a single line.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    chars: usize,
+    graphemes: usize,
+    words: usize,
+    is_blank: bool,
+}
+
+impl Line {
+    fn of(text: &str) -> Self {
+        let text = text.strip_suffix('\r').unwrap_or(text);
+
+        Self {
+            chars: text.chars().count(),
+            graphemes: text.graphemes(true).count(),
+            words: text.unicode_words().count(),
+            is_blank: text.trim().is_empty(),
+        }
+    }
+}
+
+/// The counts of every line of a [`Content`] and their totals, kept up to
+/// date with its changes so only the edited lines are counted again.
+#[derive(Debug, Clone)]
+pub(super) struct Tracker {
+    lines: Vec<Line>,
+    counts: Counts,
+}
+
+impl Tracker {
+    pub(super) fn new(text: &str) -> Self {
+        let lines: Vec<_> = text.split('\n').map(Line::of).collect();
+
+        Self {
+            counts: Counts::from_lines(&lines),
+            lines,
+        }
+    }
+
+    /// Counts again the lines touched by the [`Change`], given the lines of
+    /// the text after it.
+    pub(super) fn update<'a>(
+        &mut self,
+        change: &Change,
+        line: impl Fn(usize) -> Option<super::Line<'a>>,
+    ) {
+        let first = change.start.0.min(self.lines.len());
+        let end = (change.end.0 + 1).clamp(first, self.lines.len());
+        let inserted = change.text.matches('\n').count() + 1;
+
+        // The line after the edited ones may start or stop a paragraph
+        let paragraphs = |lines: &[Line], end: usize| {
+            (first..=end.min(lines.len().saturating_sub(1)))
+                .filter(|&index| starts_paragraph(lines, index))
+                .count()
+        };
+
+        self.counts.paragraphs -= paragraphs(&self.lines, end);
+
+        let lines = (first..first + inserted)
+            .map(|index| line(index).map_or_else(|| Line::of(""), |line| Line::of(&line.text)));
+
+        for line in self.lines.splice(first..end, lines) {
+            self.counts.chars -= line.chars;
+            self.counts.graphemes -= line.graphemes;
+            self.counts.words -= line.words;
+        }
+
+        for line in &self.lines[first..first + inserted] {
+            self.counts.chars += line.chars;
+            self.counts.graphemes += line.graphemes;
+            self.counts.words += line.words;
+        }
+
+        self.counts.lines = self.lines.len();
+        self.counts.paragraphs += paragraphs(&self.lines, first + inserted);
+    }
+
+    pub(super) fn counts(&self) -> Counts {
+        self.counts
+    }
+}
+
+/// Returns true if the line at the given index is the first of a paragraph.
+fn starts_paragraph(lines: &[Line], index: usize) -> bool {
+    !lines[index].is_blank && (index == 0 || lines[index - 1].is_blank)
+}
+
+impl<R> Content<R>
+where
+    R: text::Renderer,
+{
+    /// Returns the [`Stats`] of the [`Content`].
+    ///
+    /// The counts of the text are kept up to date with every edit after the
+    /// first call, so only the edited lines are counted again.
+    pub fn stats(&self) -> Stats {
+        Stats {
+            text: self.counts(),
+            selection: self.selection().map(|selection| Counts::of(&selection)),
+        }
+    }
+
+    /// Returns the [`Counts`] of the whole text of the [`Content`].
+    pub(super) fn counts(&self) -> Counts {
+        if let Some(tracker) = &self.0.borrow().stats {
+            return tracker.counts();
+        }
+
+        let tracker = Tracker::new(&self.text());
+        let counts = tracker.counts();
+
+        self.0.borrow_mut().stats = Some(tracker);
+
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::textbox::LineEnding;
+
+    /// Updates the [`Tracker`] of `before` with the [`Change`] replacing the
+    /// given range with the given text, returning its counts along with the
+    /// ones of the text after the [`Change`] counted from scratch.
+    fn update(before: &str, range: std::ops::Range<usize>, text: &str) -> (Counts, Counts) {
+        let position = |offset: usize| {
+            let before = &before[..offset];
+
+            match before.rfind('\n') {
+                Some(index) => (before.matches('\n').count(), offset - index - 1),
+                None => (0, offset),
+            }
+        };
+
+        let after = format!("{}{text}{}", &before[..range.start], &before[range.end..]);
+        let lines: Vec<_> = after.split('\n').collect();
+
+        let change = Change {
+            start: position(range.start),
+            end: position(range.end),
+            range,
+            text: text.to_owned(),
+            version: 1,
+        };
+
+        let mut tracker = Tracker::new(before);
+
+        tracker.update(&change, |index| {
+            lines.get(index).map(|text| crate::textbox::Line {
+                text: Cow::Borrowed(*text),
+                ending: LineEnding::Lf,
+            })
+        });
+
+        (tracker.counts(), Counts::of(&after))
+    }
+
+    #[test]
+    fn counts_text() {
+        let counts = Counts::of("Hello, world!\n\nHéllo again\n");
+
+        assert_eq!(
+            counts,
+            Counts {
+                chars: 24,
+                graphemes: 24,
+                words: 4,
+                lines: 4,
+                paragraphs: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn updates_an_edited_line() {
+        let (tracked, counted) = update("one two\nthree", 3..3, " and");
+
+        assert_eq!(tracked, counted);
+        assert_eq!(tracked.words, 4);
+    }
+
+    #[test]
+    fn updates_inserted_lines() {
+        let (tracked, counted) = update("one\ntwo", 3..3, "\n\nthree\n");
+
+        assert_eq!(tracked, counted);
+        assert_eq!((tracked.lines, tracked.paragraphs), (5, 3));
+    }
+
+    #[test]
+    fn updates_deleted_lines() {
+        let (tracked, counted) = update("one\n\ntwo\n\nthree", 3..9, "");
+
+        assert_eq!(tracked, counted);
+        assert_eq!((tracked.lines, tracked.paragraphs), (2, 1));
+    }
+
+    #[test]
+    fn merges_paragraphs() {
+        let (tracked, counted) = update("one\n\ntwo", 4..5, "");
+
+        assert_eq!(tracked, counted);
+        assert_eq!(tracked.paragraphs, 1);
+    }
+
+    #[test]
+    fn splits_paragraphs() {
+        let (tracked, counted) = update("one\ntwo", 3..4, "\n\n");
+
+        assert_eq!(tracked, counted);
+        assert_eq!(tracked.paragraphs, 2);
+    }
+}
@@ -1,8 +1,11 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
 use super::{Motion, State, Status};
 use crate::core::keyboard::{self, key};
 use crate::core::mouse;
 use crate::core::text;
-use crate::core::{Event, Padding, Point, Rectangle, Vector};
+use crate::core::{Event, Padding, Point, Rectangle, Vector, input_method, window};
 use iced_graphics::core::SmolStr;
 
 /// A key press.
@@ -19,7 +22,12 @@ pub struct KeyPress {
 }
 
 /// A binding to an action in the [`TextEditor`].
+///
+/// With the `serde` feature, bindings can be serialized, like the macros
+/// recorded with [`Binding::StartRecording`]. Custom bindings are skipped.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum Binding<Message> {
     /// Unfocus the [`TextEditor`].
     Unfocus,
@@ -32,8 +40,10 @@ pub enum Binding<Message> {
     /// Paste the clipboard contents in the [`TextEditor`].
     Paste,
     /// Apply a [`Motion`].
+    #[cfg_attr(feature = "serde", serde(with = "motion_name"))]
     Move(Motion),
     /// Select text with a given [`Motion`].
+    #[cfg_attr(feature = "serde", serde(with = "motion_name"))]
     Select(Motion),
     /// Select the word at the current cursor.
     SelectWord,
@@ -41,6 +51,11 @@ pub enum Binding<Message> {
     SelectLine,
     /// Select the entire buffer.
     SelectAll,
+    /// Select the given amount of whole lines, starting from the current one.
+    SelectLines(usize),
+    /// Move the cursor one character to the left, unless it is at the start
+    /// of its line.
+    MoveLeftInLine,
     /// Insert the given character.
     Insert(char),
     /// Break the current line.
@@ -49,9 +64,49 @@ pub enum Binding<Message> {
     Backspace,
     /// Delete the next character.
     Delete,
+    /// Delete the previous word.
+    DeleteWordBackward,
+    /// Delete the next word.
+    DeleteWordForward,
+    /// Delete from the start of the line to the cursor.
+    DeleteToLineStart,
+    /// Delete from the cursor to the end of the line.
+    DeleteToLineEnd,
+    /// Duplicate the current line.
+    DuplicateLine,
+    /// Duplicate the current line, keeping the cursor in the copy above.
+    DuplicateLineUp,
+    /// Swap the current line with the one above.
+    MoveLineUp,
+    /// Swap the current line with the one below.
+    MoveLineDown,
+    /// Join the current line with the one below.
+    JoinLines,
+    /// Swap the characters around the cursor.
+    TransposeChars,
+    /// Cut from the cursor to the end of the line into the kill ring.
+    KillToLineEnd,
+    /// Cut the selection into the kill ring.
+    KillSelection,
+    /// Copy the selection into the kill ring.
+    CopyToKillRing,
+    /// Paste the last entry of the kill ring.
+    Yank,
+    /// Replace the text just pasted from the kill ring with the previous entry.
+    YankPop,
+    /// Start recording the bindings applied to the [`TextEditor`] as a macro.
+    StartRecording,
+    /// Stop recording the macro, producing it with the closure set with
+    /// [`TextBox::on_record`].
+    ///
+    /// [`TextBox::on_record`]: super::TextBox::on_record
+    StopRecording,
+    /// Replay the last recorded macro the given amount of times.
+    Replay(usize),
     /// A sequence of bindings to execute.
     Sequence(Vec<Self>),
     /// Produce the given message.
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom(Message),
 }
 
@@ -72,12 +127,42 @@ impl<Message> Binding<Message> {
 
         match key.as_ref() {
             keyboard::Key::Named(key::Named::Enter) => Some(Self::Enter),
+            keyboard::Key::Named(key::Named::Backspace)
+                if modifiers.macos_command() || (modifiers.jump() && modifiers.shift()) =>
+            {
+                Some(Self::DeleteToLineStart)
+            }
+            keyboard::Key::Named(key::Named::Backspace) if modifiers.jump() => {
+                Some(Self::DeleteWordBackward)
+            }
             keyboard::Key::Named(key::Named::Backspace) => Some(Self::Backspace),
+            keyboard::Key::Named(key::Named::Delete)
+                if modifiers.macos_command() || (modifiers.jump() && modifiers.shift()) =>
+            {
+                Some(Self::DeleteToLineEnd)
+            }
+            keyboard::Key::Named(key::Named::Delete) if modifiers.jump() => {
+                Some(Self::DeleteWordForward)
+            }
             keyboard::Key::Named(key::Named::Delete)
                 if text.is_none() || text.as_deref() == Some("\u{7f}") =>
             {
                 Some(Self::Delete)
             }
+            keyboard::Key::Named(key::Named::ArrowUp) if modifiers.alt() => {
+                Some(if modifiers.shift() {
+                    Self::DuplicateLineUp
+                } else {
+                    Self::MoveLineUp
+                })
+            }
+            keyboard::Key::Named(key::Named::ArrowDown) if modifiers.alt() => {
+                Some(if modifiers.shift() {
+                    Self::DuplicateLine
+                } else {
+                    Self::MoveLineDown
+                })
+            }
             keyboard::Key::Named(key::Named::Escape) => Some(Self::Unfocus),
             keyboard::Key::Character("c") if modifiers.command() => Some(Self::Copy),
             keyboard::Key::Character("x") if modifiers.command() => Some(Self::Cut),
@@ -120,16 +205,253 @@ impl<Message> Binding<Message> {
             }
         }
     }
+
+    /// Returns the built-in [`Binding`] with the given kebab-case name, like
+    /// `"select-all"`, `"delete-word-backward"` or `"move-word-left"`.
+    ///
+    /// Motions are named after their [`Motion`], prefixed by `move-` or `select-`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(motion) = name.strip_prefix("move-").and_then(motion_from_name) {
+            return Some(Self::Move(motion));
+        }
+
+        if let Some(motion) = name.strip_prefix("select-").and_then(motion_from_name) {
+            return Some(Self::Select(motion));
+        }
+
+        Some(match name {
+            "unfocus" => Self::Unfocus,
+            "submit" => Self::Submit,
+            "copy" => Self::Copy,
+            "cut" => Self::Cut,
+            "paste" => Self::Paste,
+            "select-word" => Self::SelectWord,
+            "select-line" => Self::SelectLine,
+            "select-all" => Self::SelectAll,
+            "select-lines" => Self::SelectLines(1),
+            "move-left-in-line" => Self::MoveLeftInLine,
+            "enter" => Self::Enter,
+            "backspace" => Self::Backspace,
+            "delete" => Self::Delete,
+            "delete-word-backward" => Self::DeleteWordBackward,
+            "delete-word-forward" => Self::DeleteWordForward,
+            "delete-to-line-start" => Self::DeleteToLineStart,
+            "delete-to-line-end" => Self::DeleteToLineEnd,
+            "duplicate-line" => Self::DuplicateLine,
+            "duplicate-line-up" => Self::DuplicateLineUp,
+            "move-line-up" => Self::MoveLineUp,
+            "move-line-down" => Self::MoveLineDown,
+            "join-lines" => Self::JoinLines,
+            "transpose-chars" => Self::TransposeChars,
+            "kill-to-line-end" => Self::KillToLineEnd,
+            "kill-selection" => Self::KillSelection,
+            "copy-to-kill-ring" => Self::CopyToKillRing,
+            "yank" => Self::Yank,
+            "yank-pop" => Self::YankPop,
+            "start-recording" => Self::StartRecording,
+            "stop-recording" => Self::StopRecording,
+            "replay" => Self::Replay(1),
+            _ => return None,
+        })
+    }
+
+    /// Returns true if the [`Binding`] may move the caret, either by applying
+    /// some [`Motion`] or by editing the text.
+    pub(super) fn moves_caret(&self) -> bool {
+        match self {
+            Self::Cut
+            | Self::Paste
+            | Self::Move(_)
+            | Self::Select(_)
+            | Self::SelectWord
+            | Self::SelectLine
+            | Self::SelectLines(_)
+            | Self::MoveLeftInLine
+            | Self::Insert(_)
+            | Self::Enter
+            | Self::Backspace
+            | Self::Delete
+            | Self::DeleteWordBackward
+            | Self::DeleteWordForward
+            | Self::DeleteToLineStart
+            | Self::DeleteToLineEnd
+            | Self::DuplicateLine
+            | Self::DuplicateLineUp
+            | Self::MoveLineUp
+            | Self::MoveLineDown
+            | Self::JoinLines
+            | Self::TransposeChars
+            | Self::KillToLineEnd
+            | Self::KillSelection
+            | Self::Yank
+            | Self::YankPop
+            | Self::Replay(_) => true,
+            Self::Sequence(sequence) => sequence.iter().any(Self::moves_caret),
+            Self::Unfocus
+            | Self::Submit
+            | Self::Copy
+            | Self::CopyToKillRing
+            | Self::SelectAll
+            | Self::StartRecording
+            | Self::StopRecording
+            | Self::Custom(_) => false,
+        }
+    }
+
+    /// Returns true if applying the [`Binding`] reads the text or the cursor
+    /// of the [`Content`], so it must wait until the actions published before
+    /// it are performed.
+    ///
+    /// Insertions only read it to enforce the constraints of the text, if
+    /// any. Sequences are expanded before they are applied.
+    ///
+    /// [`Content`]: super::Content
+    pub(super) fn reads_content(&self, is_constrained: bool) -> bool {
+        match self {
+            Self::Submit
+            | Self::Copy
+            | Self::Cut
+            | Self::DeleteWordBackward
+            | Self::DeleteWordForward
+            | Self::DeleteToLineStart
+            | Self::DeleteToLineEnd
+            | Self::DuplicateLine
+            | Self::DuplicateLineUp
+            | Self::MoveLineUp
+            | Self::MoveLineDown
+            | Self::JoinLines
+            | Self::TransposeChars
+            | Self::KillToLineEnd
+            | Self::KillSelection
+            | Self::CopyToKillRing
+            | Self::SelectLines(_)
+            | Self::MoveLeftInLine
+            | Self::YankPop => true,
+            Self::Paste
+            | Self::Insert(_)
+            | Self::Enter
+            | Self::Backspace
+            | Self::Delete
+            | Self::Yank => is_constrained,
+            Self::Unfocus
+            | Self::Move(_)
+            | Self::Select(_)
+            | Self::SelectWord
+            | Self::SelectLine
+            | Self::SelectAll
+            | Self::StartRecording
+            | Self::StopRecording
+            | Self::Replay(_)
+            | Self::Sequence(_)
+            | Self::Custom(_) => false,
+        }
+    }
+
+    /// Returns the [`Motion`] that selects the word or the part of the line
+    /// deleted by the [`Binding`], if any, along with the [`Binding`] that
+    /// deletes the selection.
+    pub(super) fn deletion(&self) -> Option<(Motion, Self)> {
+        Some(match self {
+            Self::DeleteWordBackward => (Motion::WordLeft, Self::Backspace),
+            Self::DeleteWordForward => (Motion::WordRight, Self::Delete),
+            Self::DeleteToLineStart => (Motion::Home, Self::Backspace),
+            Self::DeleteToLineEnd => (Motion::End, Self::Delete),
+            _ => return None,
+        })
+    }
+
+    /// Returns the [`Binding`] to apply in place of this one in a secure
+    /// [`TextEditor`], which must not reveal its text; or `None` to ignore it.
+    ///
+    /// Word motions would reveal the word boundaries, so they go to the line
+    /// boundaries instead.
+    pub(super) fn secured(self) -> Option<Self> {
+        Some(match self {
+            Self::Copy | Self::Cut | Self::CopyToKillRing => return None,
+            Self::KillSelection => Self::Delete,
+            Self::KillToLineEnd => Self::DeleteToLineEnd,
+            Self::Move(Motion::WordLeft) => Self::Move(Motion::Home),
+            Self::Move(Motion::WordRight) => Self::Move(Motion::End),
+            Self::Select(Motion::WordLeft) => Self::Select(Motion::Home),
+            Self::Select(Motion::WordRight) => Self::Select(Motion::End),
+            Self::SelectWord => Self::SelectAll,
+            Self::DeleteWordBackward => Self::DeleteToLineStart,
+            Self::DeleteWordForward => Self::DeleteToLineEnd,
+            Self::Sequence(sequence) => {
+                Self::Sequence(sequence.into_iter().filter_map(Self::secured).collect())
+            }
+            binding => binding,
+        })
+    }
+
+    /// Returns the [`Binding`] with another message type, unless it is a
+    /// custom one. Custom bindings are dropped from sequences.
+    pub(super) fn to_builtin<T>(&self) -> Option<Binding<T>> {
+        Some(match self {
+            Self::Unfocus => Binding::Unfocus,
+            Self::Submit => Binding::Submit,
+            Self::Copy => Binding::Copy,
+            Self::Cut => Binding::Cut,
+            Self::Paste => Binding::Paste,
+            Self::Move(motion) => Binding::Move(*motion),
+            Self::Select(motion) => Binding::Select(*motion),
+            Self::SelectWord => Binding::SelectWord,
+            Self::SelectLine => Binding::SelectLine,
+            Self::SelectAll => Binding::SelectAll,
+            Self::SelectLines(count) => Binding::SelectLines(*count),
+            Self::MoveLeftInLine => Binding::MoveLeftInLine,
+            Self::Insert(c) => Binding::Insert(*c),
+            Self::Enter => Binding::Enter,
+            Self::Backspace => Binding::Backspace,
+            Self::Delete => Binding::Delete,
+            Self::DeleteWordBackward => Binding::DeleteWordBackward,
+            Self::DeleteWordForward => Binding::DeleteWordForward,
+            Self::DeleteToLineStart => Binding::DeleteToLineStart,
+            Self::DeleteToLineEnd => Binding::DeleteToLineEnd,
+            Self::DuplicateLine => Binding::DuplicateLine,
+            Self::DuplicateLineUp => Binding::DuplicateLineUp,
+            Self::MoveLineUp => Binding::MoveLineUp,
+            Self::MoveLineDown => Binding::MoveLineDown,
+            Self::JoinLines => Binding::JoinLines,
+            Self::TransposeChars => Binding::TransposeChars,
+            Self::KillToLineEnd => Binding::KillToLineEnd,
+            Self::KillSelection => Binding::KillSelection,
+            Self::CopyToKillRing => Binding::CopyToKillRing,
+            Self::Yank => Binding::Yank,
+            Self::YankPop => Binding::YankPop,
+            Self::StartRecording => Binding::StartRecording,
+            Self::StopRecording => Binding::StopRecording,
+            Self::Replay(count) => Binding::Replay(*count),
+            Self::Sequence(sequence) => {
+                Binding::Sequence(sequence.iter().filter_map(Self::to_builtin).collect())
+            }
+            Self::Custom(_) => return None,
+        })
+    }
 }
 
 pub(super) enum Update<Message> {
     Click(mouse::Click),
     Drag(Point),
-    Release,
+    Release(Option<Point>),
+    FileDrop(PathBuf, Point),
+    ContextMenu(Point),
+    PrimaryPaste(Point),
     Scroll(f32),
+    InputMethod(Ime),
     Binding(Binding<Message>),
 }
 
+/// An input method update.
+pub(super) enum Ime {
+    Toggle(bool),
+    Preedit {
+        content: String,
+        selection: Option<Range<usize>>,
+    },
+    Commit(String),
+}
+
 impl<Message> Update<Message> {
     pub(super) fn from_event<Link, H, Renderer>(
         event: &Event,
@@ -146,13 +468,18 @@ impl<Message> Update<Message> {
     {
         let binding = |binding| Some(Update::Binding(binding));
 
+        // The position of the cursor relative to the (possibly scrolled) text
+        let position_in = |cursor: mouse::Cursor| {
+            cursor.position_in(bounds).map(|position| {
+                position - Vector::new(padding.left, padding.top)
+                    + Vector::new(state.scroll_offset_x, state.scroll_offset)
+            })
+        };
+
         match event {
             Event::Mouse(event) => match event {
                 mouse::Event::ButtonPressed(mouse::Button::Left) => {
-                    if let Some(cursor_position) = cursor.position_in(bounds) {
-                        let cursor_position =
-                            cursor_position - Vector::new(padding.top, padding.left);
-
+                    if let Some(cursor_position) = position_in(cursor) {
                         let click = mouse::Click::new(
                             cursor_position,
                             mouse::Button::Left,
@@ -166,28 +493,42 @@ impl<Message> Update<Message> {
                         None
                     }
                 }
-                mouse::Event::ButtonReleased(mouse::Button::Left) => Some(Update::Release),
-                mouse::Event::CursorMoved { .. } => match state.drag_click {
-                    Some(mouse::click::Kind::Single) => {
-                        let cursor_position =
-                            cursor.position_in(bounds)? - Vector::new(padding.top, padding.left);
-
-                        Some(Update::Drag(cursor_position))
-                    }
-                    _ => None,
-                },
+                mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                    Some(Update::ContextMenu(position_in(cursor)?))
+                }
+                mouse::Event::ButtonPressed(mouse::Button::Middle) => {
+                    Some(Update::PrimaryPaste(position_in(cursor)?))
+                }
+                mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                    Some(Update::Release(position_in(cursor)))
+                }
+                // Dragging after a double or triple click extends the selection
+                // by words or lines, respectively
+                mouse::Event::CursorMoved { .. } if state.drag_click.is_some() => {
+                    Some(Update::Drag(position_in(cursor)?))
+                }
                 mouse::Event::WheelScrolled { delta } if cursor.is_over(bounds) => {
-                    Some(Update::Scroll(match delta {
-                        mouse::ScrollDelta::Lines { y, .. } => {
-                            if y.abs() > 0.0 {
-                                y.signum() * -(y.abs() * 4.0).max(1.0)
-                            } else {
-                                0.0
-                            }
-                        }
-                        mouse::ScrollDelta::Pixels { y, .. } => -y / 4.0,
+                    Some(Update::Scroll(scroll_lines(delta)))
+                }
+                _ => None,
+            },
+            Event::Window(window::Event::FileDropped(path)) => {
+                Some(Update::FileDrop(path.clone(), position_in(cursor)?))
+            }
+            Event::InputMethod(event) => match event {
+                input_method::Event::Opened if state.focus.is_some() => {
+                    Some(Update::InputMethod(Ime::Toggle(true)))
+                }
+                input_method::Event::Closed => Some(Update::InputMethod(Ime::Toggle(false))),
+                input_method::Event::Preedit(content, selection) if state.focus.is_some() => {
+                    Some(Update::InputMethod(Ime::Preedit {
+                        content: content.clone(),
+                        selection: selection.clone(),
                     }))
                 }
+                input_method::Event::Commit(content) if state.focus.is_some() => {
+                    Some(Update::InputMethod(Ime::Commit(content.clone())))
+                }
                 _ => None,
             },
             Event::Keyboard(keyboard::Event::KeyPressed {
@@ -221,6 +562,74 @@ impl<Message> Update<Message> {
     }
 }
 
+/// Returns the amount of lines to scroll for the given [`mouse::ScrollDelta`].
+pub(super) fn scroll_lines(delta: &mouse::ScrollDelta) -> f32 {
+    match delta {
+        mouse::ScrollDelta::Lines { y, .. } => {
+            if y.abs() > 0.0 {
+                y.signum() * -(y.abs() * 4.0).max(1.0)
+            } else {
+                0.0
+            }
+        }
+        mouse::ScrollDelta::Pixels { y, .. } => -y / 4.0,
+    }
+}
+
+/// (De)serializes a [`Motion`] by its name, like `word-left`.
+#[cfg(feature = "serde")]
+mod motion_name {
+    use super::{Motion, motion_from_name};
+
+    pub(super) fn serialize<S>(motion: &Motion, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(match motion {
+            Motion::Left => "left",
+            Motion::Right => "right",
+            Motion::Up => "up",
+            Motion::Down => "down",
+            Motion::WordLeft => "word-left",
+            Motion::WordRight => "word-right",
+            Motion::Home => "home",
+            Motion::End => "end",
+            Motion::PageUp => "page-up",
+            Motion::PageDown => "page-down",
+            Motion::DocumentStart => "document-start",
+            Motion::DocumentEnd => "document-end",
+        })
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Motion, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+        motion_from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown motion `{name}`")))
+    }
+}
+
+fn motion_from_name(name: &str) -> Option<Motion> {
+    match name {
+        "left" => Some(Motion::Left),
+        "right" => Some(Motion::Right),
+        "up" => Some(Motion::Up),
+        "down" => Some(Motion::Down),
+        "word-left" => Some(Motion::WordLeft),
+        "word-right" => Some(Motion::WordRight),
+        "home" => Some(Motion::Home),
+        "end" => Some(Motion::End),
+        "page-up" => Some(Motion::PageUp),
+        "page-down" => Some(Motion::PageDown),
+        "document-start" => Some(Motion::DocumentStart),
+        "document-end" => Some(Motion::DocumentEnd),
+        _ => None,
+    }
+}
+
 fn motion(key: key::Named) -> Option<Motion> {
     match key {
         key::Named::ArrowLeft => Some(Motion::Left),
@@ -0,0 +1,116 @@
+//! Show the message of a failed validation under a text box.
+use super::{Catalog, Status};
+use crate::core::text::{self, Paragraph, Text};
+use crate::core::{
+    Border, Layout, Padding, Pixels, Point, Rectangle, Size, alignment, layout, mouse, overlay,
+    renderer,
+};
+use crate::widget::text::{LineHeight, Shaping, Wrapping};
+
+/// The padding around the message of a failed validation.
+const PADDING: Padding = Padding {
+    top: 2.0,
+    right: 4.0,
+    bottom: 2.0,
+    left: 4.0,
+};
+
+/// The overlay of the message of a failed validation, right under its
+/// [`TextBox`].
+///
+/// [`TextBox`]: super::TextBox
+pub(super) struct Overlay<'a, 'b, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    pub(super) message: String,
+    pub(super) class: &'b Theme::Class<'a>,
+    pub(super) position: Point,
+    pub(super) width: f32,
+    pub(super) text_size: Pixels,
+    pub(super) line_height: LineHeight,
+    pub(super) font: Renderer::Font,
+}
+
+impl<Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for Overlay<'_, '_, Theme, Renderer>
+where
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    fn layout(&mut self, _renderer: &Renderer, bounds: Size) -> layout::Node {
+        let paragraph = Renderer::Paragraph::with_text(Text {
+            content: self.message.as_str(),
+            bounds: Size::new((self.width - PADDING.horizontal()).max(0.0), f32::INFINITY),
+            size: self.text_size,
+            line_height: self.line_height,
+            font: self.font,
+            align_x: text::Alignment::Left,
+            align_y: alignment::Vertical::Top,
+            shaping: Shaping::Advanced,
+            wrapping: Wrapping::WordOrGlyph,
+        });
+
+        let size = Size::new(
+            self.width,
+            paragraph.min_bounds().height + PADDING.vertical(),
+        );
+
+        // Keep the message inside of the window
+        let position = Point::new(
+            self.position.x.min(bounds.width - size.width).max(0.0),
+            self.position.y.min(bounds.height - size.height).max(0.0),
+        );
+
+        layout::Node::new(size).move_to(position)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        _defaults: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+    ) {
+        let style = theme.style(self.class, Status::Invalid);
+        let bounds = layout.bounds();
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border: Border {
+                    radius: style.border.radius,
+                    ..Border::default()
+                },
+                ..renderer::Quad::default()
+            },
+            style.background,
+        );
+
+        let text_bounds = Rectangle {
+            x: bounds.x + PADDING.left,
+            y: bounds.y + PADDING.top,
+            width: bounds.width - PADDING.horizontal(),
+            height: bounds.height - PADDING.vertical(),
+        };
+
+        renderer.fill_text(
+            Text {
+                content: self.message.clone(),
+                bounds: text_bounds.size(),
+                size: self.text_size,
+                line_height: self.line_height,
+                font: self.font,
+                align_x: text::Alignment::Left,
+                align_y: alignment::Vertical::Top,
+                shaping: Shaping::Advanced,
+                wrapping: Wrapping::WordOrGlyph,
+            },
+            text_bounds.position(),
+            style.error,
+            bounds,
+        );
+    }
+}